@@ -0,0 +1,112 @@
+//! Procedural macros of KCG.
+//!
+//! `include_schema!` runs scan, lint and code-gen at compile time and expands to generated items.
+//! ```ignore
+//! kcg_macros::include_schema!("api1.yaml", include = "mine1.rs", skip = [SampleType1, SampleType2]);
+//! ```
+//! Paths are relative to the directory of `Cargo.toml` of the crate using this macro.
+//! Options are named after `kcg::Builder` methods.
+//! - `include = "path"`: Prefix code for generated code.
+//! - `skip = [Type, ...]`: Skips code-gen for types.
+//! - `derive = [Trait, ...]`: Derives traits on all generated types.
+//! - `builders = true`: Generates builder types for product types.
+//! - `validation = true`: Generates `Validate` implementations.
+//! - `format_types = ["<format>=<type>", ...]`: Rust types for formatted strings.
+//! - `collision_prefix = "..."`, `collision_suffix = "..."`: Renames colliding type names.
+//! - `ir = true`: Reads input as KCG IR JSON file.
+//!
+//! Lint errors are reported as compile errors at the input path.
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{bracketed, Token, Ident, LitStr, LitBool};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+
+#[proc_macro]
+pub fn include_schema(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let args = syn::parse_macro_input!(input as Args);
+    match args.expand() {
+        Ok(x) => x.into(),
+        Err(x) => x.to_compile_error().into(),
+    }
+}
+
+/// Arguments of `include_schema!`.
+struct Args {
+    input: LitStr,
+    builder: kcg::Builder,
+    /// Absolute paths to read files.
+    /// Expanded code includes them to recompile on changes.
+    files: Vec<String>,
+}
+
+impl Parse for Args {
+    fn parse(stream: ParseStream) -> syn::Result<Args> {
+        let input = stream.parse::<LitStr>()?;
+        let path = resolve(&input.value());
+        let mut args = Args {
+            builder: kcg::Builder::new().cargo_metadata(false).input(&path),
+            files: vec![path],
+            input,
+        };
+        while !stream.is_empty() {
+            stream.parse::<Token![,]>()?;
+            if stream.is_empty() { break }
+            let key = stream.parse::<Ident>()?;
+            stream.parse::<Token![=]>()?;
+            let b = args.builder;
+            args.builder = match key.to_string().as_str() {
+                "include" => {
+                    let path = resolve(&stream.parse::<LitStr>()?.value());
+                    args.files.push(path.clone());
+                    b.include(path)
+                },
+                "skip" => parse_list::<Ident>(stream)?.iter().fold(b, |b,x| b.skip(&x.to_string())),
+                "derive" => parse_list::<syn::Path>(stream)?.iter().fold(b, |b,x| b.derive(&x.to_token_stream().to_string())),
+                "builders" => b.builders(stream.parse::<LitBool>()?.value),
+                "validation" => b.validation(stream.parse::<LitBool>()?.value),
+                "ir" => b.ir(stream.parse::<LitBool>()?.value),
+                "format_types" => {
+                    let mut b = b;
+                    for x in parse_list::<LitStr>(stream)? {
+                        match x.value().split_once('=') {
+                            Some((format, r#type)) => b = b.format_type(format, r#type),
+                            None => return Err(syn::Error::new(x.span(), "expected `<format>=<type>`")),
+                        }
+                    }
+                    b
+                },
+                "collision_prefix" => b.collision_prefix(&stream.parse::<LitStr>()?.value()),
+                "collision_suffix" => b.collision_suffix(&stream.parse::<LitStr>()?.value()),
+                x => return Err(syn::Error::new(key.span(), format!("unknown option `{}`", x))),
+            };
+        }
+        Ok(args)
+    }
+}
+
+impl Args {
+    fn expand(&self) -> syn::Result<TokenStream> {
+        let code = self.builder.generate().map_err(|e| syn::Error::new(self.input.span(), e.to_string().trim_end()))?;
+        let items = code.parse::<TokenStream>().map_err(|e| syn::Error::new(self.input.span(), e.to_string()))?;
+        let files = &self.files;
+        Ok(quote! {
+            #(const _: &[u8] = include_bytes!(#files);)*
+            #items
+        })
+    }
+}
+
+/// Parses `[a, b, ...]`.
+fn parse_list<T:Parse>(stream: ParseStream) -> syn::Result<Punctuated<T,Token![,]>> {
+    let content;
+    bracketed!(content in stream);
+    Punctuated::parse_terminated(&content)
+}
+
+/// Resolves `path` against the directory of `Cargo.toml` of the crate being compiled.
+fn resolve(path: &str) -> String {
+    let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    std::path::Path::new(&dir).join(path).to_string_lossy().into_owned()
+}
@@ -0,0 +1,20 @@
+#[allow(clippy::to_string_trait_impl)]
+mod sample1 {
+    kcg_macros::include_schema!(
+        "../tests/sample1/src.yaml",
+        include = "../tests/sample1/prelude.rs",
+        skip = [Ship],
+        derive = [Clone],
+    );
+}
+
+#[test]
+fn include_schema_round_trip() -> Result<(), serde_json::Error> {
+    use sample1::*;
+    let a = Order { id: Some(1), quantity: None };
+    let b = serde_json::from_str::<Order>(&serde_json::to_string(&a.clone())?)?;
+    assert_eq!(a, b);
+    assert_eq!(serde_json::from_str::<Fish>("\"Whale\"")?, Fish::Whale);
+    assert_eq!(serde_json::to_string(&Ship)?, "null");
+    Ok(())
+}
@@ -0,0 +1,232 @@
+//! Entry point for build scripts.
+//!
+//! ```no_run
+//! // In `main` of build.rs.
+//! kcg::Builder::new()
+//!     .input("api1.yaml")
+//!     .skip("Ship")
+//!     .generate_to(std::env::var("OUT_DIR").unwrap())
+//!     .unwrap();
+//! ```
+//! Generated file is named after input file. (`api1.rs` for `api1.yaml`)
+//! Include it with `include!(concat!(env!("OUT_DIR"), "/api1.rs"));`.
+
+mod test;
+
+use crate::lint::{Lint, Context, Path, Log, Severity};
+use crate::model::Doc1;
+use crate::scan::openapi3;
+use crate::codegen::CodeGen;
+use crate::codegen::rust::Options;
+use crate::config::{Map, LintLevel};
+
+pub type Result<T> = std::result::Result<T,Box<dyn std::error::Error>>;
+
+/// Generates Rust code from a schema in build scripts.
+/// - Prints `cargo:rerun-if-changed` for the schema and prelude files.
+/// - Prints lint warnings and errors as `cargo:warning`, and fails if there's any lint error.
+/// - Command line tool runs `kcg gen` and `kcg lint` with this too.
+#[derive(Clone)]
+pub struct Builder {
+    cargo_metadata: bool,
+    input: Option<std::path::PathBuf>,
+    ir: bool,
+    prelude: Option<std::path::PathBuf>,
+    collision_prefix: String,
+    collision_suffix: String,
+    lints: Map<String,LintLevel>,
+    options: Options,
+}
+
+impl Default for Builder {
+    fn default() -> Builder {
+        Builder {
+            cargo_metadata: true,
+            input: None,
+            ir: false,
+            prelude: None,
+            collision_prefix: String::new(),
+            collision_suffix: String::new(),
+            lints: Map::new(),
+            options: Options::default(),
+        }
+    }
+}
+
+impl Builder {
+    pub fn new() -> Builder {
+        Builder::default()
+    }
+    /// Prints `cargo:` lines for build scripts. Enabled by default.
+    pub fn cargo_metadata(mut self, enabled: bool) -> Builder {
+        self.cargo_metadata = enabled;
+        self
+    }
+    /// Path to source OpenAPI 3.0 schema file.
+    pub fn input(mut self, path: impl AsRef<std::path::Path>) -> Builder {
+        self.input = Some(path.as_ref().to_path_buf());
+        self
+    }
+    /// Reads input as KCG IR JSON file.
+    pub fn ir(mut self, ir: bool) -> Builder {
+        self.ir = ir;
+        self
+    }
+    /// Path to prefix code for generated code.
+    pub fn include(mut self, path: impl AsRef<std::path::Path>) -> Builder {
+        self.prelude = Some(path.as_ref().to_path_buf());
+        self
+    }
+    /// Skips code-gen for a type. Prelude is supposed to provide it.
+    pub fn skip(mut self, type_name: &str) -> Builder {
+        self.options.skipping_type_names.push(type_name.to_string());
+        self
+    }
+    /// Derives a trait on all generated types.
+    pub fn derive(mut self, r#trait: &str) -> Builder {
+        self.options.derives.push(r#trait.to_string());
+        self
+    }
+    /// Generates builder types for product types.
+    pub fn builders(mut self, builders: bool) -> Builder {
+        self.options.builders = builders;
+        self
+    }
+    /// Generates `Validate` implementations.
+    pub fn validation(mut self, validation: bool) -> Builder {
+        self.options.validation = validation;
+        self
+    }
+    /// Rust type for strings with a `format`. See `Options::format_types` for presets.
+    pub fn format_type(mut self, format: &str, r#type: &str) -> Builder {
+        self.options.format_types.push((format.to_string(), r#type.to_string()));
+        self
+    }
+    /// Prefix to rename types with names colliding with Rust names or other type names.
+    pub fn collision_prefix(mut self, prefix: &str) -> Builder {
+        self.collision_prefix = prefix.to_string();
+        self
+    }
+    /// Suffix to rename types with names colliding with Rust names or other type names.
+    pub fn collision_suffix(mut self, suffix: &str) -> Builder {
+        self.collision_suffix = suffix.to_string();
+        self
+    }
+    /// Overrides severity of a lint rule keyed by its ID. (`crate::lint::RULES`) Same with `[lints]` in `kcg.toml`.
+    /// Errors cannot be lowered.
+    pub fn lint_level(mut self, rule: &str, level: LintLevel) -> Builder {
+        self.lints.insert(rule.to_string(), level);
+        self
+    }
+
+    /// Generates Rust code.
+    pub fn generate(&self) -> Result<String> {
+        self.generate_reporting(&mut |x| self.print_cargo_warning(x))
+    }
+    /// Generates Rust code, and passes lint logs adjusted by lint levels to `report`.
+    /// Command line tool uses this to print logs in its own way.
+    pub fn generate_reporting(&self, report: &mut dyn FnMut(&Log)) -> Result<String> {
+        let k = self.read_model_reporting(report)?;
+        let mut x = Context::default();
+        self.options.lint(Path::default(), &mut x);
+        self.options.lint_reserved_names(&k, Path::default(), &mut x);
+        self.report(x, report)?;
+
+        let mut code = String::new();
+        if let Some(prelude) = &self.prelude {
+            code.push_str(&self.read_file(prelude)?);
+        }
+        code.push_str("\n\n");
+        code.push_str(&rustfmt(k.code(&self.options)));
+        Ok(code)
+    }
+    /// Reads input into a model checked for code-gen, and passes lint logs adjusted by lint levels to `report`.
+    /// Code-gen options are not checked.
+    pub fn read_model_reporting(&self, report: &mut dyn FnMut(&Log)) -> Result<Doc1> {
+        let input = self.input.as_ref().ok_or("no input schema")?;
+        let src = self.read_file(input)?;
+        let mut k = match self.ir {
+            false => {
+                let oas = serde_yaml::from_str::<openapi3::model::Doc>(&src)?;
+                let mut x = Context::default();
+                oas.lint(Path::default(), &mut x);
+                self.report(x, report)?;
+                oas.scan(Path::default()).map_err(Rejected)?
+            },
+            true => serde_json::from_str::<Doc1>(&src)?,
+        };
+        k.rename_conflicting_types(&self.collision_prefix, &self.collision_suffix);
+        let mut x = Context::default();
+        k.lint(Path::default(), &mut x);
+        crate::codegen::rust::lint_member_idents(&k, Path::default(), &mut x);
+        k.lint_skipped_types(&self.options.skipping_type_names, Path::default(), &mut x);
+        self.report(x, report)?;
+        Ok(k)
+    }
+    /// Generates Rust code into a file named after input file in `dir`.
+    /// Returns path to the file.
+    pub fn generate_to(&self, dir: impl AsRef<std::path::Path>) -> Result<std::path::PathBuf> {
+        let input = self.input.as_ref().ok_or("no input schema")?;
+        let stem = input.file_stem().ok_or("input path has no file name")?;
+        let path = dir.as_ref().join(format!("{}.rs", stem.to_string_lossy()));
+        std::fs::write(&path, self.generate()?)?;
+        Ok(path)
+    }
+
+    /// Reads a file and asks Cargo to rerun build script if it changes.
+    fn read_file(&self, path: &std::path::Path) -> Result<String> {
+        if self.cargo_metadata {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+        std::fs::read_to_string(path).map_err(|e| format!("cannot read `{}`: {}", path.display(), e).into())
+    }
+    /// Passes logs adjusted by lint levels to `report`, and fails if there's any error.
+    fn report(&self, mut context: Context, report: &mut dyn FnMut(&Log)) -> Result<()> {
+        crate::config::adjust_lints(&self.lints, &mut context);
+        for x in context.logs.iter() {
+            report(x);
+        }
+        context.check()
+    }
+    /// Prints lint warnings and errors as Cargo warnings.
+    fn print_cargo_warning(&self, log: &Log) {
+        if self.cargo_metadata && log.severity != Severity::Info {
+            println!("cargo:warning={}", log);
+        }
+    }
+}
+
+/// Formats generated code with `rustfmt` to make it stable with `cargo fmt`. Prelude is kept as is.
+/// Returns code as is if `rustfmt` is unavailable or fails. (`RUSTFMT` environment variable or `rustfmt` in `PATH`)
+fn rustfmt(code: String) -> String {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+    let program = std::env::var_os("RUSTFMT").unwrap_or_else(|| "rustfmt".into());
+    let run = || -> Option<String> {
+        let mut child = Command::new(program)
+            .args(["--edition", "2021", "--quiet"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn().ok()?;
+        let mut stdin = child.stdin.take()?;
+        // Writes in another thread to avoid deadlock on pipe buffers full of output.
+        let writer = std::thread::spawn({ let code = code.clone(); move || stdin.write_all(code.as_bytes()) });
+        let output = child.wait_with_output().ok()?;
+        writer.join().ok()?.ok()?;
+        if !output.status.success() { return None }
+        String::from_utf8(output.stdout).ok()
+    };
+    run().unwrap_or(code)
+}
+
+/// Input is rejected by scan.
+/// Lint rejections are `crate::lint::LintError`, and other errors are failures to read or parse files.
+#[derive(Debug)]
+pub struct Rejected(pub String);
+impl std::fmt::Display for Rejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+impl std::error::Error for Rejected {}
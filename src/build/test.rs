@@ -0,0 +1,46 @@
+#![cfg(test)]
+
+use super::*;
+
+#[test]
+fn test_builder() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("kcg-test-builder-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let path = Builder::new()
+        .input("tests/sample1/src.yaml")
+        .include("tests/sample1/prelude.rs")
+        .skip("Ship")
+        .derive("Hash")
+        .generate_to(&dir)?;
+    assert_eq!(path, dir.join("src.rs"));
+    let code = std::fs::read_to_string(&path)?;
+    assert!(code.starts_with(&std::fs::read_to_string("tests/sample1/prelude.rs")?));
+    assert!(code.contains("pub struct Order {"));
+    assert!(!code.contains("pub struct Ship {"));
+    std::fs::remove_dir_all(&dir)?;
+
+    assert!(Builder::new().generate().is_err());
+    assert!(Builder::new().input("tests/sample1/src.yaml").skip("Ghost").generate().is_ok());
+    assert!(Builder::new().input("tests/no-such-file.yaml").generate().is_err());
+    // Options are checked before code-gen.
+    assert!(Builder::new().cargo_metadata(false).input("tests/sample1/src.yaml").derive("serde::").generate().is_err());
+    assert!(Builder::new().cargo_metadata(false).input("tests/sample1/src.yaml").format_type("uuid", "Vec<").generate().is_err());
+    // Lint levels apply to warnings, but errors cannot be lowered.
+    let ghost = Builder::new().cargo_metadata(false).input("tests/sample1/src.yaml").skip("Ghost");
+    assert!(ghost.clone().lint_level("skipped-type-undefined", LintLevel::Error).generate().is_err());
+    assert!(ghost.clone().lint_level("skipped-type-undefined", LintLevel::Allow).derive("serde::").generate().is_err());
+    // Levels are keyed by rule IDs, and unknown IDs are errors.
+    assert!(ghost.lint_level("skipped type is not defined", LintLevel::Allow).generate().is_err());
+    Ok(())
+}
+
+#[test]
+fn test_rustfmt_stable() -> Result<()> {
+    if std::process::Command::new("rustfmt").arg("--version").output().is_err() { return Ok(()) }
+    let code = Builder::new().cargo_metadata(false).input("tests/sample1/src.yaml").builders(true).validation(true).generate()?;
+    let code = code.trim_start();
+    assert_eq!(rustfmt(code.to_string()), code);
+    // `prettyplease` puts derives in separate attributes, and `rustfmt` merges them.
+    assert!(code.contains("#[derive(Eq, PartialEq, Debug)]"));
+    Ok(())
+}
@@ -2,6 +2,10 @@ pub mod rust;
 // mod swift;
 // mod typescript;
 
+/// Generates code in a target language.
+/// Documents are generated as whole files, and parts of them are generated as standalone items.
 pub trait CodeGen {
-    fn code(&self) -> String;
+    /// Code-gen options for the target language.
+    type Options;
+    fn code(&self, options: &Self::Options) -> String;
 }
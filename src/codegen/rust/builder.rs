@@ -0,0 +1,101 @@
+//! Builder code-gen for product types.
+//! Builders check required fields at runtime.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use crate::model::*;
+use super::codegen::{deprecated_code, Context};
+use super::ident::*;
+
+impl message::KProdType {
+    pub(super) fn builder_code(&self, context: &Context) -> TokenStream {
+        let fields = self.fields.iter().map(|x| {
+            let ident = snake_case_ident(&x.name);
+            BuilderField {
+                ident: ident_code(&ident),
+                name: unraw(&ident).to_string(),
+                optional: x.content.optional,
+                default: self.default_fn_code(x),
+                deprecated: deprecated_code(&x.deprecated),
+                storage: message::KContentStorage { optional: false, nullable: x.content.nullable, array: x.content.array, r#type: x.content.r#type.clone() }.code_in(context, context.is_boxed(&self.name, &x.name)),
+            }
+        }).collect::<Vec<_>>();
+        let storages = fields.iter().map(|x| {
+            let (ident, storage) = (&x.ident, &x.storage);
+            quote!(#ident: Option<#storage>,)
+        });
+        let setters = fields.iter().map(|x| {
+            let (ident, storage, deprecated) = (&x.ident, &x.storage, &x.deprecated);
+            quote! {
+                #deprecated
+                pub fn #ident(mut self, value: #storage) -> Self {
+                    self.#ident = Some(value);
+                    self
+                }
+            }
+        });
+        let checks = fields.iter().filter(|x| !x.optional && x.default.is_none()).map(|x| {
+            let (ident, name) = (&x.ident, &x.name);
+            quote!(if self.#ident.is_none() { missing.push(#name); })
+        }).collect::<Vec<_>>();
+        let inits = fields.iter().map(|x| {
+            let ident = &x.ident;
+            match (x.optional, &x.default) {
+                (true, None) => quote!(#ident: self.#ident,),
+                (true, Some(f)) => quote!(#ident: self.#ident.or_else(#f),),
+                (false, None) => quote!(#ident: self.#ident.unwrap(),),
+                (false, Some(f)) => quote!(#ident: self.#ident.unwrap_or_else(#f),),
+            }
+        });
+        let check = match checks.is_empty() {
+            true => TokenStream::new(),
+            false => quote! {
+                let mut missing = Vec::new();
+                #(#checks)*
+                if !missing.is_empty() { return Err(missing) }
+            },
+        };
+        let allow = context.allow_deprecated_code();
+        let name = ident_code(&self.name);
+        let builder = format_ident!("{}Builder", unraw(&self.name));
+        let build_doc = format!(" Builds `{}`.", unraw(&self.name));
+        quote! {
+            #allow
+            #[derive(Default)]
+            pub struct #builder {
+                #(#storages)*
+            }
+            #allow
+            impl #builder {
+                #(#setters)*
+                #[doc = #build_doc]
+                /// Fails with names of missing required fields.
+                pub fn build(self) -> Result<#name, Vec<&'static str>> {
+                    #check
+                    Ok(#name {
+                        #(#inits)*
+                    })
+                }
+            }
+            #allow
+            impl #name {
+                pub fn builder() -> #builder {
+                    #builder::default()
+                }
+            }
+        }
+    }
+}
+
+struct BuilderField {
+    ident: proc_macro2::Ident,
+    /// Field name reported when missing.
+    name: String,
+    optional: bool,
+    /// Path to function making default value.
+    default: Option<TokenStream>,
+    /// Deprecation attribute of the field.
+    deprecated: TokenStream,
+    /// Rust type of non-optional field content.
+    storage: syn::Type,
+}
@@ -1,220 +1,633 @@
-use extend::ext;
-use indoc::formatdoc;
+use proc_macro2::TokenStream;
+use quote::quote;
 use crate::model::*;
 use crate::codegen::*;
+use super::ident::*;
+use super::validation::prim_check_code;
 
-impl Doc1 {
-    pub fn code(&self, skipping_type_names: &[String]) -> String {
+/// Rust code-gen options.
+#[derive(Clone)]
+#[derive(Default)]
+pub struct Options {
+    /// KCG won't make code for types with names in `skippings`.
+    pub skipping_type_names: Vec<String>,
+    /// Additional traits to derive on all generated types.
+    /// Per-type derives are defined by `KTypeAttrs::derive`.
+    pub derives: Vec<String>,
+    /// Generates builder types for product types.
+    pub builders: bool,
+    /// Generates `Validate` implementations checking schema constraints at runtime.
+    pub validation: bool,
+    /// Rust types for formatted strings. Pairs of `format` and Rust type.
+    /// Strings with formats not listed here are made as `String`.
+    /// Lint rejects formats KCG does not support. (`date-tme`, `email`, ...)
+    /// These preset names are expanded. Lint rejects them for other formats.
+    /// - `chrono` for `date-time` and `date`: `chrono::DateTime<chrono::Utc>` and `chrono::NaiveDate`.
+    /// - `uuid` for `uuid`: `uuid::Uuid`.
+    /// - `url` for `uri`: `url::Url`.
+    /// - `base64` for `byte`: `Base64Bytes` which is generated together and stores decoded bytes.
+    pub format_types: Vec<(String,String)>,
+}
+
+/// Formats available as keys of `Options::format_types`.
+pub(super) const FORMATS: &[&str] = &["date-time", "date", "uuid", "uri", "byte", "binary"];
+
+/// Preset names of `Options::format_types` and formats they can be used for.
+pub(super) const FORMAT_PRESETS: &[(&str, &[&str])] = &[
+    ("chrono", &["date-time", "date"]),
+    ("uuid", &["uuid"]),
+    ("url", &["uri"]),
+    ("base64", &["byte"]),
+];
+
+/// Document-wide facts for Rust code-gen.
+/// Some code-gen decisions depend on other types, and they are collected here before generating code.
+#[derive(Default)]
+pub(super) struct Context {
+    pub(super) options: Options,
+    /// Members to be stored in `Box` to break infinitely sized recursive types.
+    /// Pairs of type name and member name.
+    boxed_members: Vec<(String,String)>,
+    /// Types containing floating-point numbers transitively.
+    /// These types cannot implement `Eq`, `Ord` and `Hash`.
+    float_types: Vec<String>,
+    /// Types which cannot derive `Default` directly or transitively.
+    non_default_types: Vec<String>,
+    /// Generated product/sum-types implementing `Validate`.
+    validated_types: Vec<String>,
+    /// Whether anything is deprecated.
+    /// Generated code using deprecated items allows it to avoid warnings.
+    deprecations: bool,
+}
+impl Context {
+    fn new(doc: &Doc1, options: &Options) -> Context {
+        use message::KPrimType::*;
+        Context {
+            options: options.clone(),
+            boxed_members: doc.recursive_type_edges().iter().map(|x| (x.from.to_string(), x.member.to_string())).collect(),
+            float_types: doc.types_containing(|x| matches!(x, F32 | F64)).iter().map(|x| x.to_string()).collect(),
+            non_default_types: types_without_default(doc),
+            validated_types: doc.types.iter()
+                .filter(|x| is_defined(x, options) && matches!(x, message::KType::Prod(_) | message::KType::Sum(_)))
+                .map(|x| x.name().to_string())
+                .collect(),
+            deprecations: doc.types.iter().any(|x| x.has_deprecation()),
+        }
+    }
+    /// Context for a type generated without its document.
+    /// Nothing is boxed, and every referenced type is assumed to support every trait.
+    fn standalone(options: &Options) -> Context {
+        Context { options: options.clone(), ..Context::default() }
+    }
+    /// Makes an attribute allowing use of deprecated items if anything is deprecated.
+    pub(super) fn allow_deprecated_code(&self) -> TokenStream {
+        match self.deprecations {
+            true => quote!(#[allow(deprecated)]),
+            false => TokenStream::new(),
+        }
+    }
+    /// Rust type for a primitive type.
+    pub(super) fn prim_type_code(&self, prim: &message::KPrimType) -> syn::Type {
+        let format = match format_name(prim) {
+            Some(x) => x,
+            None => return prim.type_code(),
+        };
+        use message::KPrimType::*;
+        let mapped = match self.options.format_types.iter().find(|(x,_)| x == format) {
+            Some((_, x)) => x.as_str(),
+            None => return prim.type_code(),
+        };
+        match (prim, mapped) {
+            (DateTime, "chrono") => syn::parse_quote!(chrono::DateTime<chrono::Utc>),
+            (Date, "chrono") => syn::parse_quote!(chrono::NaiveDate),
+            (Uuid, "uuid") => syn::parse_quote!(uuid::Uuid),
+            (Uri, "url") => syn::parse_quote!(url::Url),
+            (Byte, "base64") => syn::parse_quote!(Base64Bytes),
+            (_, x) => rust_type_code(x),
+        }
+    }
+    /// Whether `Base64Bytes` support type is needed.
+    fn uses_base64(&self) -> bool {
+        let r#type = self.prim_type_code(&message::KPrimType::Byte);
+        quote!(#r#type).to_string() == "Base64Bytes"
+    }
+    pub(super) fn is_validated(&self, type_name: &str) -> bool {
+        self.validated_types.iter().any(|x| x == type_name)
+    }
+
+    pub(super) fn is_boxed(&self, type_name: &str, member_name: &str) -> bool {
+        self.boxed_members.iter().any(|(a,b)| a == type_name && b == member_name)
+    }
+    /// Makes derive attributes for a type.
+    /// Derives which cannot be implemented for the type are dropped.
+    fn derive_code(&self, type_name: &str, attrs: &message::KTypeAttrs) -> TokenStream {
+        self.derive_code_excluding(type_name, attrs, &[])
+    }
+    /// - `excluded`: Traits which must not be derived for the type.
+    fn derive_code_excluding(&self, type_name: &str, attrs: &message::KTypeAttrs, excluded: &[&str]) -> TokenStream {
+        const NON_FLOAT_TRAITS: &[&str] = &["Eq", "Ord", "Hash"];
+        let floating = self.float_types.iter().any(|x| x == type_name);
+        let no_default = self.non_default_types.iter().any(|x| x == type_name);
+        let dropped = |x: &str| excluded.contains(&x) || (floating && NON_FLOAT_TRAITS.contains(&x)) || (no_default && x == "Default");
+        let base: &[&[&str]] = &[&["Serialize","Deserialize"], &["Eq","PartialEq"], &["Debug"]];
+        let mut extra = Vec::<&str>::new();
+        for x in self.options.derives.iter().chain(attrs.derive.iter()) {
+            if !base.concat().contains(&x.as_str()) && !extra.contains(&x.as_str()) { extra.push(x) }
+        }
+        base.iter().copied().chain(std::iter::once(extra.as_slice()))
+            .map(|group| group.iter().copied().filter(|x| !dropped(x)).map(rust_path_code).collect::<Vec<_>>())
+            .filter(|group| !group.is_empty())
+            .map(|group| quote!(#[derive(#(#group),*)]))
+            .collect()
+    }
+}
+
+/// Finds types which cannot derive `Default`.
+/// - Enum/sum-types have no obvious default case.
+/// - New-types with constraints can be made only by `TryFrom`.
+/// - Types storing values of above types directly. Optional and array members are empty by default.
+///
+/// Product-types implementing `Default` with field defaults are not counted.
+fn types_without_default(doc: &Doc1) -> Vec<String> {
+    use message::KType::*;
+    let mut z = doc.types.iter()
+        .filter(|x| x.attrs().rust_type.is_none())
+        .filter(|x| match x {
+            Enum(_) | Sum(_) => true,
+            New(x) => x.constraints != message::KConstraints::default(),
+            Prod(_) => false,
+        })
+        .map(|x| x.name())
+        .collect::<Vec<_>>();
+    fn requirements(x: &message::KType) -> Vec<&str> {
+        let refs = match x {
+            New(x) => vec![&x.origin],
+            Prod(x) if !x.has_default() => x.fields.iter()
+                .filter(|x| !x.content.optional && !x.content.nullable && !x.content.array)
+                .map(|x| &x.content.r#type)
+                .collect(),
+            _ => vec![],
+        };
+        refs.into_iter().filter_map(|x| match x { message::KTypeRef::Def(x) => Some(x.as_str()), _ => None }).collect()
+    }
+    loop {
+        let found = doc.types.iter()
+            .filter(|x| x.attrs().rust_type.is_none() && !z.contains(&x.name()))
+            .filter(|x| requirements(x).iter().any(|x| z.contains(x)))
+            .map(|x| x.name())
+            .collect::<Vec<_>>();
+        if found.is_empty() { return z.iter().map(|x| x.to_string()).collect() }
+        z.extend(found);
+    }
+}
+
+impl CodeGen for Doc1 {
+    type Options = Options;
+    fn code(&self, options: &Options) -> String {
         // TODO: Implement `funcs` code-gen.
-        let mut types_codes = Vec::new();
+        let context = Context::new(self, options);
+        let mut code = quote!(use serde_derive::{Serialize, Deserialize};);
+        if options.validation {
+            code.extend(super::validation::validation_support_code());
+        }
+        if context.uses_base64() {
+            code.extend(base64_support_code());
+        }
         for x in self.types.iter() {
-            if !skipping_type_names.contains(&x.name().to_string()) {
-                types_codes.push(x.code());
+            if is_generated(x, options) {
+                code.extend(x.code_in(&context));
             }
         }
-        formatdoc!(r#"
-            use serde_derive::{{Serialize, Deserialize}};
-            {types}
-        "#,
-        types=types_codes.join(""))
-        // types=self.types.iter().filter(|x| !skipping_type_names.contains(&x.name().to_string())).collect::<Vec<_>>().code())
+        unparse(code)
     }
 }
-impl CodeGen for Doc1 {
-    fn code(&self) -> String {
-        // TODO: Implement `funcs` code-gen.
-        formatdoc!(r#"
-            use serde_derive::{{Serialize, Deserialize}};
-
-            {types}
-        "#,
-        types=self.types.code())
+impl message::KType {
+    fn has_deprecation(&self) -> bool {
+        use message::KType::*;
+        self.attrs().deprecated.is_some() || match self {
+            New(_) => false,
+            Enum(x) => x.cases.iter().any(|x| x.deprecated.is_some()),
+            Sum(x) => x.variants.iter().any(|x| x.deprecated.is_some()),
+            Prod(x) => x.fields.iter().any(|x| x.deprecated.is_some()),
+        }
+    }
+}
+/// Formats generated items.
+/// Panics if `code` is not valid Rust items, which means a bug in code-gen.
+pub(super) fn unparse(code: TokenStream) -> String {
+    match syn::parse2::<syn::File>(code) {
+        Ok(x) => prettyplease::unparse(&x),
+        Err(x) => panic!("code-gen made invalid Rust code: {}", x),
+    }
+}
+/// Parses a Rust type designated by schema or options.
+pub(super) fn rust_type_code(x: &str) -> syn::Type {
+    syn::parse_str(x).unwrap_or_else(|_| panic!("`{}` is not a Rust type", x))
+}
+/// Parses a Rust trait path designated by schema or options.
+fn rust_path_code(x: &str) -> syn::Path {
+    syn::parse_str(x).unwrap_or_else(|_| panic!("`{}` is not a Rust path", x))
+}
+/// Makes doc comment attributes. Each line becomes an attribute.
+pub(super) fn doc_code(comment: &str) -> TokenStream {
+    if comment.is_empty() { return TokenStream::new() }
+    comment.split('\n').map(|line| match line.is_empty() {
+        true => quote!(#[doc = ""]),
+        false => { let line = format!(" {}", line); quote!(#[doc = #line]) },
+    }).collect()
+}
+/// Makes `#[deprecated]` attribute for a deprecation note.
+pub(super) fn deprecated_code(note: &Option<String>) -> TokenStream {
+    match note.as_deref() {
+        None => TokenStream::new(),
+        Some("") => quote!(#[deprecated]),
+        Some(x) => quote!(#[deprecated(note = #x)]),
     }
 }
+/// Wraps an expression or a pattern in `depth` layers of `Some`.
+pub(super) fn some_code(code: TokenStream, depth: usize) -> TokenStream {
+    (0..depth).fold(code, |x, _| quote!(Some(#x)))
+}
+/// Makes an integer literal expression.
+pub(super) fn int_code(x: i64) -> TokenStream {
+    let literal = proc_macro2::Literal::u64_unsuffixed(x.unsigned_abs());
+    if x < 0 { quote!(-#literal) } else { quote!(#literal) }
+}
+/// Makes a floating-point literal expression.
+pub(super) fn float_code(x: f64) -> TokenStream {
+    let literal = proc_macro2::Literal::f64_unsuffixed(x.abs());
+    if x.is_sign_negative() { quote!(-#literal) } else { quote!(#literal) }
+}
+/// Formats a type in compact form for attributes taking types in strings.
+fn type_string(r#type: &syn::Type) -> String {
+    let file: syn::File = syn::parse_quote!(type T = #r#type;);
+    let code = prettyplease::unparse(&file);
+    code.trim().trim_start_matches("type T = ").trim_end_matches(';').to_string()
+}
+/// Whether code-gen makes code for the type.
+/// Skipped types are supposed to be provided by prelude.
+fn is_generated(x: &message::KType, options: &Options) -> bool {
+    !x.attrs().skip && !options.skipping_type_names.iter().any(|name| name == x.name())
+}
+/// Whether code-gen makes type definition for the type.
+/// Types aliased to Rust types get only aliases, so they have no `Validate` implementations.
+fn is_defined(x: &message::KType, options: &Options) -> bool {
+    is_generated(x, options) && x.attrs().rust_type.is_none()
+}
 impl CodeGen for message::KType {
-    fn code(&self) -> String {
+    type Options = Options;
+    fn code(&self, options: &Options) -> String {
+        unparse(self.code_in(&Context::standalone(options)))
+    }
+}
+impl message::KType {
+    fn code_in(&self, context: &Context) -> TokenStream {
         use message::KType::*;
+        if let Some(x) = self.attrs().rust_type.as_ref() {
+            let deprecated = deprecated_code(&self.attrs().deprecated);
+            let name = ident_code(self.name());
+            let origin = rust_type_code(x);
+            return quote!(#deprecated pub type #name = #origin;);
+        }
         match self {
-            New(x) => x.code(),
-            Enum(x) => x.code(),
-            Sum(x) => x.code(),
-            Prod(x) => x.code(),
+            New(x) => x.code_in(context),
+            Enum(x) => x.code_in(context),
+            Sum(x) => x.code_in(context),
+            Prod(x) => x.code_in(context),
         }
     }
 }
 impl CodeGen for message::KNewType {
-    fn code(&self) -> String {
-        formatdoc!("
-            pub type {name} = {origin};
-        ",
-        name=self.name,
-        origin=self.origin.code())
+    type Options = Options;
+    fn code(&self, options: &Options) -> String {
+        unparse(self.code_in(&Context::standalone(options)))
+    }
+}
+impl message::KNewType {
+    /// New-types with constraints are made as real types which can be made only by `TryFrom`.
+    /// Deserialization also goes through `TryFrom`, so invalid values cannot be represented.
+    fn code_in(&self, context: &Context) -> TokenStream {
+        let name = ident_code(&self.name);
+        let origin = self.origin.code_in(context);
+        let deprecated = deprecated_code(&self.attrs.deprecated);
+        if self.constraints == message::KConstraints::default() {
+            return quote!(#deprecated pub type #name = #origin;);
+        }
+        let checks = match &self.origin {
+            message::KTypeRef::Prim(x) => prim_check_code(x, &self.constraints, &|message| quote!(return Err(#message);)),
+            _ => vec![],
+        };
+        let allow = context.allow_deprecated_code();
+        let doc = doc_code(&self.comment);
+        // `Default` would make a value bypassing `TryFrom`.
+        let derive = context.derive_code_excluding(&self.name, &self.attrs, &["Default"]);
+        let try_from = type_string(&origin);
+        quote! {
+            #allow
+            #doc
+            #deprecated
+            #derive
+            #[serde(try_from = #try_from)]
+            pub struct #name(#origin);
+            #allow
+            impl std::convert::TryFrom<#origin> for #name {
+                type Error = &'static str;
+                fn try_from(inner: #origin) -> Result<Self, Self::Error> {
+                    let value = &inner;
+                    #(#checks)*
+                    Ok(#name(inner))
+                }
+            }
+            #allow
+            impl From<#name> for #origin {
+                fn from(x: #name) -> #origin {
+                    x.0
+                }
+            }
+            #allow
+            impl std::ops::Deref for #name {
+                type Target = #origin;
+                fn deref(&self) -> &#origin {
+                    &self.0
+                }
+            }
+        }
     }
 }
 impl CodeGen for message::KEnumType {
-    fn code(&self) -> String {
-        formatdoc!(r#"
-            #[derive(Serialize,Deserialize)]
-            #[derive(Eq,PartialEq)]
-            #[derive(Debug)]
-            pub enum {name} {{
-            {cases}
-            }}
-            impl std::str::FromStr for {name} {{
+    type Options = Options;
+    fn code(&self, options: &Options) -> String {
+        unparse(self.code_in(&Context::standalone(options)))
+    }
+}
+impl message::KEnumType {
+    fn code_in(&self, context: &Context) -> TokenStream {
+        let name = ident_code(&self.name);
+        let allow = context.allow_deprecated_code();
+        let doc = doc_code(&self.comment);
+        let deprecated = deprecated_code(&self.attrs.deprecated);
+        let derive = context.derive_code(&self.name, &self.attrs);
+        let cases = self.cases.iter().map(|x| x.code_in());
+        let from_str_arms = self.cases.iter().map(|x| {
+            let (ident, serialized) = (ident_code(&x.ident()), &x.name);
+            quote!(#serialized => Ok(#ident),)
+        });
+        let to_str_arms = self.cases.iter().map(|x| {
+            let (ident, serialized) = (ident_code(&x.ident()), &x.name);
+            quote!(#ident => #serialized.to_string(),)
+        });
+        quote! {
+            #allow
+            #doc
+            #deprecated
+            #derive
+            pub enum #name {
+                #(#cases)*
+            }
+            #allow
+            impl std::str::FromStr for #name {
                 type Err = String;
-                fn from_str(s: &str) -> Result<Self, Self::Err> {{
-                    use {name}::*;
-                    match s {{
-            {from_str_cases}
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    use #name::*;
+                    match s {
+                        #(#from_str_arms)*
                         _ => Err("unknown case name".to_string()),
-                    }}
-                }}
-            }}
-            impl std::string::ToString for {name} {{
-                fn to_string(&self) -> String {{
-                    use {name}::*;
-                    match self {{
-            {to_str_cases}
-                    }}
-                }}
-            }}
-        "#,
-        name=self.name,
-        cases=self.cases.code().indent(),
-        from_str_cases=self.cases.iter().map(from_str_code).collect::<Vec<_>>().join("\n").indent().indent().indent(),
-        to_str_cases=self.cases.iter().map(to_str_code).collect::<Vec<_>>().join("\n").indent().indent().indent())
-    }
-}
-fn from_str_code(s:&message::KEnumTypeCase) -> String {
-    format!(r#""{}" => Ok({}),"#, s.name, s.name)
-}
-fn to_str_code(s:&message::KEnumTypeCase) -> String {
-    format!(r#"{} => "{}".to_string(),"#, s.name, s.name)
-}
-impl CodeGen for message::KEnumTypeCase {
-    fn code(&self) -> String {
-        formatdoc!("
-            {name},
-        ",
-        name=self.name)
+                    }
+                }
+            }
+            #allow
+            impl std::string::ToString for #name {
+                fn to_string(&self) -> String {
+                    use #name::*;
+                    match self {
+                        #(#to_str_arms)*
+                    }
+                }
+            }
+        }
+    }
+}
+impl message::KEnumTypeCase {
+    fn ident(&self) -> String {
+        camel_case_ident(self.var_name.as_ref().unwrap_or(&self.name))
+    }
+    fn code_in(&self) -> TokenStream {
+        let ident = self.ident();
+        let doc = doc_code(&self.comment);
+        let deprecated = deprecated_code(&self.deprecated);
+        let rename = rename_code(unraw(&ident), &self.name);
+        let ident = ident_code(&ident);
+        quote!(#doc #deprecated #rename #ident,)
     }
 }
 impl CodeGen for message::KSumType {
-    fn code(&self) -> String {
-        formatdoc!(r#"
-            {comment}
-            #[derive(Serialize,Deserialize)]
-            #[derive(Eq,PartialEq)]
-            #[derive(Debug)]
-            #[serde(tag="{tag}")]
-            pub enum {name} {{
-            {variants}
-            }}
-        "#,
-        tag=self.discriminant,
-        comment=self.comment.code_documentation(),
-        name=self.name,
-        variants=self.variants.code().indent())
-    }
-}
-impl CodeGen for message::KSumTypeVariant {
-    fn code(&self) -> String {
-        formatdoc!("
-            {comment}
-            {name}({type}),
-        ",
-        comment=self.comment.code_documentation(),
-        name=self.name,
-        type=self.content.code())
+    type Options = Options;
+    fn code(&self, options: &Options) -> String {
+        unparse(self.code_in(&Context::standalone(options)))
+    }
+}
+impl message::KSumType {
+    fn code_in(&self, context: &Context) -> TokenStream {
+        let name = ident_code(&self.name);
+        let allow = context.allow_deprecated_code();
+        let doc = doc_code(&self.comment);
+        let deprecated = deprecated_code(&self.attrs.deprecated);
+        let derive = context.derive_code(&self.name, &self.attrs);
+        let tagging = self.tagging_code();
+        let variants = self.variants.iter().map(|x| x.code_in(context, context.is_boxed(&self.name, &x.name)));
+        let validation = match context.options.validation {
+            true => self.validation_code(context),
+            false => TokenStream::new(),
+        };
+        quote! {
+            #allow
+            #doc
+            #deprecated
+            #derive
+            #tagging
+            pub enum #name {
+                #(#variants)*
+            }
+            #validation
+        }
+    }
+    fn tagging_code(&self) -> TokenStream {
+        use message::KSumTypeTagging::*;
+        let tag = &self.discriminant;
+        match &self.tagging {
+            Internal => quote!(#[serde(tag = #tag)]),
+            External => TokenStream::new(),
+            Adjacent { content } => quote!(#[serde(tag = #tag, content = #content)]),
+            Untagged => quote!(#[serde(untagged)]),
+        }
+    }
+}
+impl message::KSumTypeVariant {
+    fn code_in(&self, context: &Context, boxed: bool) -> TokenStream {
+        let ident = camel_case_ident(&self.name);
+        let doc = doc_code(&self.comment);
+        let deprecated = deprecated_code(&self.deprecated);
+        let rename = rename_code(unraw(&ident), &self.tag);
+        let ident = ident_code(&ident);
+        match self.content.r#type {
+            message::KTypeRef::Unit => quote!(#doc #deprecated #rename #ident,),
+            _ => {
+                let r#type = self.content.code_in(context, boxed);
+                quote!(#doc #deprecated #rename #ident(#r#type),)
+            },
+        }
     }
 }
+/// Makes serde rename attribute if serialized name differs from Rust name.
+fn rename_code(name: &str, serialized_name: &str) -> TokenStream {
+    if name == serialized_name { return TokenStream::new() }
+    quote!(#[serde(rename = #serialized_name)])
+}
 
 impl CodeGen for message::KProdType {
-    fn code(&self) -> String {
-        formatdoc!("
-            {comment}
-            #[derive(Serialize,Deserialize)]
-            #[derive(Eq,PartialEq)]
-            #[derive(Debug)]
-            pub struct {name} {{
-            {fields}
-            }}
-        ",
-        comment=self.comment.code_documentation(),
-        name=self.name,
-        fields=self.fields.code().indent())
-    }
-}
-impl CodeGen for message::KProdTypeField {
-    fn code(&self) -> String {
-        formatdoc!("
-            {comment}
-            pub {name}: {type},
-        ",
-        comment=self.comment.code_documentation(),
-        name=self.name,
-        type=self.content.code())
-    }
-}
-impl CodeGen for message::KContentStorage {
-    fn code(&self) -> String {
-        match (self.array, self.optional) {
-            (false,false) => self.r#type.code(),
-            (true,false) => format!("Vec<{name}>", name=self.r#type.code()),
-            (false,true) => format!("Option<{name}>", name=self.r#type.code()),
-            (true,true) => format!("Option<Vec<{name}>>", name=self.r#type.code()),
+    type Options = Options;
+    fn code(&self, options: &Options) -> String {
+        unparse(self.code_in(&Context::standalone(options)))
+    }
+}
+impl message::KProdType {
+    fn code_in(&self, context: &Context) -> TokenStream {
+        let name = ident_code(&self.name);
+        let allow = context.allow_deprecated_code();
+        let doc = doc_code(&self.comment);
+        let deprecated = deprecated_code(&self.attrs.deprecated);
+        // `Default` is implemented with field defaults.
+        let derive = context.derive_code_excluding(&self.name, &self.attrs, if self.has_default() { &["Default"] } else { &[] });
+        let fields = self.fields.iter().map(|x| x.code_in(context, context.is_boxed(&self.name, &x.name), self.default_fn_path(x).as_deref()));
+        let default = self.default_code(context);
+        let builder = match context.options.builders {
+            true => self.builder_code(context),
+            false => TokenStream::new(),
+        };
+        let validation = match context.options.validation {
+            true => self.validation_code(context),
+            false => TokenStream::new(),
+        };
+        quote! {
+            #allow
+            #doc
+            #deprecated
+            #derive
+            pub struct #name {
+                #(#fields)*
+            }
+            #default
+            #builder
+            #validation
         }
     }
 }
+impl message::KProdTypeField {
+    /// - `default_fn_path`: Path to function making default value of this field.
+    fn code_in(&self, context: &Context, boxed: bool, default_fn_path: Option<&str>) -> TokenStream {
+        let ident = snake_case_ident(&self.name);
+        let doc = doc_code(&self.comment);
+        let deprecated = deprecated_code(&self.deprecated);
+        let rename = rename_code(unraw(&ident), &self.name);
+        let default = match (self.content.optional && self.content.nullable, default_fn_path) {
+            // Distinguishes absent (`None`) and `null` (`Some(None)`).
+            (true, None) => quote!(#[serde(default, skip_serializing_if = "Option::is_none", with = "::serde_with::rust::double_option")]),
+            (true, Some(x)) => quote!(#[serde(default = #x, skip_serializing_if = "Option::is_none", with = "::serde_with::rust::double_option")]),
+            (false, Some(x)) => quote!(#[serde(default = #x)]),
+            (false, None) => TokenStream::new(),
+        };
+        let ident = ident_code(&ident);
+        let r#type = self.content.code_in(context, boxed);
+        quote!(#doc #deprecated #rename #default pub #ident: #r#type,)
+    }
+}
+impl message::KContentStorage {
+    /// Number of `Option` layers wrapping content.
+    pub(super) fn option_depth(&self) -> usize {
+        self.optional as usize + self.nullable as usize
+    }
+    /// - `boxed`: Stores content in `Box` to break recursive type definitions.
+    pub(super) fn code_in(&self, context: &Context, boxed: bool) -> syn::Type {
+        let mut z = self.r#type.code_in(context);
+        if boxed { z = syn::parse_quote!(Box<#z>) }
+        if self.array { z = syn::parse_quote!(Vec<#z>) }
+        for _ in 0..self.option_depth() { z = syn::parse_quote!(Option<#z>) }
+        z
+    }
+}
 
-impl CodeGen for message::KTypeRef {
-    fn code(&self) -> String {
+impl message::KTypeRef {
+    fn code_in(&self, context: &Context) -> syn::Type {
         use message::KTypeRef::*;
         match self {
-            Unit => "()".to_string(),
-            Prim(x) => x.code(),
-            Def(x) => x.clone(),
+            Unit => syn::parse_quote!(()),
+            Prim(x) => context.prim_type_code(x),
+            Def(x) => {
+                let x = ident_code(x);
+                syn::parse_quote!(#x)
+            },
         }
     }
 }
-impl CodeGen for message::KPrimType {
-    fn code(&self) -> String {
+impl message::KPrimType {
+    /// Rust type with no configured format types.
+    fn type_code(&self) -> syn::Type {
         use message::KPrimType::*;
         match self {
-            Bool => "bool",
-            I32 => "i32",
-            I64 => "i64",
-            F32 => "f32",
-            F64 => "f64",
-            String => "String",
+            Bool => syn::parse_quote!(bool),
+            I32 => syn::parse_quote!(i32),
+            I64 => syn::parse_quote!(i64),
+            F32 => syn::parse_quote!(f32),
+            F64 => syn::parse_quote!(f64),
+            String => syn::parse_quote!(String),
+            // Formatted strings without configured Rust types.
+            DateTime | Date | Uuid | Uri | Byte | Binary => syn::parse_quote!(String),
         }
-        .to_string()
     }
 }
-
-
-
-
-impl<T:CodeGen> CodeGen for Vec<T> {
-    fn code(&self) -> String {
-        self.iter().map(|x| x.code().trim().to_string()).collect::<Vec<String>>().join("\n")
+/// OpenAPI `format` name of a formatted string type.
+fn format_name(prim: &message::KPrimType) -> Option<&'static str> {
+    use message::KPrimType::*;
+    let x = match prim {
+        DateTime => "date-time",
+        Date => "date",
+        Uuid => "uuid",
+        Uri => "uri",
+        Byte => "byte",
+        Binary => "binary",
+        Bool | I32 | I64 | F32 | F64 | String => return None,
+    };
+    Some(x)
+}
+/// Makes `Base64Bytes` type which stores bytes serialized as a base64 string.
+fn base64_support_code() -> TokenStream {
+    quote! {
+        /// Bytes serialized as a base64 string. (`format: byte`)
+        #[derive(Clone)]
+        #[derive(Default)]
+        #[derive(Eq, PartialEq)]
+        #[derive(Hash)]
+        #[derive(Debug)]
+        pub struct Base64Bytes(pub Vec<u8>);
+        impl serde::Serialize for Base64Bytes {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                use base64::Engine;
+                serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(&self.0))
+            }
+        }
+        impl<'de> serde::Deserialize<'de> for Base64Bytes {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                use base64::Engine;
+                let x = <String as serde::Deserialize>::deserialize(deserializer)?;
+                base64::engine::general_purpose::STANDARD.decode(x).map(Base64Bytes).map_err(serde::de::Error::custom)
+            }
+        }
+        impl std::ops::Deref for Base64Bytes {
+            type Target = Vec<u8>;
+            fn deref(&self) -> &Vec<u8> {
+                &self.0
+            }
+        }
     }
 }
 
-#[ext(name=StringUtil)] 
-impl String {
-    fn indent(&self) -> String {
-        self.prefix("    ")
-    }
-    fn code_documentation(&self) -> String {
-        if self.is_empty() { return self.clone() }
-        self.prefix("/// ")
-    }
-    fn prefix(&self, prefix: &str) -> String {
-        self.split("\n").map(|line| {
-            let mut x = String::from(prefix);
-            x.push_str(line);
-            x
-        }).collect::<Vec<String>>().join("\n")
-    }
-}
\ No newline at end of file
@@ -0,0 +1,111 @@
+//! Default value code-gen for product types.
+//! Fields with defaults get helper functions used by serde, builders and `impl Default`.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use crate::model::*;
+use super::codegen::{float_code, int_code, some_code, Context};
+use super::ident::*;
+
+impl message::KProdType {
+    /// Whether all fields can have values without input.
+    /// KCG implements `Default` for such types.
+    pub(super) fn has_default(&self) -> bool {
+        self.fields.iter().all(|x| x.content.optional || x.default_value_code().is_some())
+    }
+    /// Path to function making default value of a field, for serde attributes.
+    /// `None` if the field has no default.
+    pub(super) fn default_fn_path(&self, field: &message::KProdTypeField) -> Option<String> {
+        field.default_value_code()?;
+        Some(format!("{}::{}", self.name, default_fn_ident(field)))
+    }
+    /// Path expression of function making default value of a field.
+    /// `None` if the field has no default.
+    pub(super) fn default_fn_code(&self, field: &message::KProdTypeField) -> Option<TokenStream> {
+        field.default_value_code()?;
+        let (name, f) = (ident_code(&self.name), default_fn_ident(field));
+        Some(quote!(#name::#f))
+    }
+    pub(super) fn default_code(&self, context: &Context) -> TokenStream {
+        let allow = context.allow_deprecated_code();
+        let name = ident_code(&self.name);
+        let fns = self.fields.iter().filter_map(|x| {
+            let f = default_fn_ident(x);
+            let r#type = x.content.code_in(context, false);
+            let value = x.default_value_code()?;
+            Some(quote! {
+                fn #f() -> #r#type {
+                    #value
+                }
+            })
+        }).collect::<Vec<_>>();
+        let mut code = match fns.is_empty() {
+            true => TokenStream::new(),
+            false => quote! {
+                #allow
+                impl #name {
+                    #(#fns)*
+                }
+            },
+        };
+        if !self.has_default() { return code }
+        let inits = self.fields.iter().map(|x| {
+            let ident = ident_code(&snake_case_ident(&x.name));
+            match self.default_fn_code(x) {
+                Some(f) => quote!(#ident: #f(),),
+                None => quote!(#ident: None,),
+            }
+        });
+        code.extend(quote! {
+            #allow
+            impl Default for #name {
+                fn default() -> Self {
+                    #name {
+                        #(#inits)*
+                    }
+                }
+            }
+        });
+        code
+    }
+}
+
+/// Name of function making default value of a field.
+fn default_fn_ident(field: &message::KProdTypeField) -> proc_macro2::Ident {
+    format_ident!("default_{}", unraw(&snake_case_ident(&field.name)))
+}
+
+impl message::KProdTypeField {
+    /// Rust expression of default value.
+    /// `None` if the field has no default or it doesn't fit to the field type.
+    pub(super) fn default_value_code(&self) -> Option<TokenStream> {
+        let value = self.default.as_ref()?;
+        let depth = self.content.option_depth();
+        if value.is_null() && self.content.nullable {
+            return Some(some_code(quote!(None), depth - 1));
+        }
+        let code = match (self.content.array, value) {
+            (false, _) => value_code(&self.content.r#type, value)?,
+            (true, serde_json::Value::Array(xs)) => {
+                let xs = xs.iter().map(|x| value_code(&self.content.r#type, x)).collect::<Option<Vec<_>>>()?;
+                quote!(vec![#(#xs),*])
+            },
+            (true, _) => return None,
+        };
+        Some(some_code(code, depth))
+    }
+}
+
+fn value_code(r#type: &message::KTypeRef, value: &serde_json::Value) -> Option<TokenStream> {
+    use message::KTypeRef::*;
+    use message::KPrimType::*;
+    use serde_json::Value;
+    let code = match (r#type, value) {
+        (Prim(Bool), Value::Bool(x)) => quote!(#x),
+        (Prim(I32 | I64), Value::Number(x)) => int_code(x.as_i64()?),
+        (Prim(F32 | F64), Value::Number(x)) => float_code(x.as_f64()?),
+        (Prim(String), Value::String(x)) => quote!(#x.to_string()),
+        _ => return None,
+    };
+    Some(code)
+}
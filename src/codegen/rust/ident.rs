@@ -0,0 +1,93 @@
+//! Maps schema names to Rust identifiers.
+//!
+//! Schema names can be anything in JSON, but Rust identifiers cannot.
+//! Mapped names can differ from original names,
+//! and code-gen is supposed to add `#[serde(rename)]` for them.
+
+/// Makes `snake_case` identifier for fields.
+pub fn snake_case_ident(name: &str) -> String {
+    let z = words(name).iter().map(|x| x.to_lowercase()).collect::<Vec<_>>().join("_");
+    escape(prefix_digit(z, "_"))
+}
+
+/// Makes `UpperCamelCase` identifier for enum cases and sum-type variants.
+pub fn camel_case_ident(name: &str) -> String {
+    let z = words(name).iter().map(|x| capitalize(x)).collect::<Vec<_>>().join("");
+    escape(prefix_digit(z, "N"))
+}
+
+/// Strips raw identifier prefix.
+/// Serde uses unprefixed names for raw identifiers.
+pub fn unraw(ident: &str) -> &str {
+    ident.strip_prefix("r#").unwrap_or(ident)
+}
+
+/// Makes identifier token from a name made by functions in this module.
+/// Raw identifiers are made for `r#` prefixed names.
+pub fn ident_code(ident: &str) -> proc_macro2::Ident {
+    match ident.strip_prefix("r#") {
+        Some(x) => proc_macro2::Ident::new_raw(x, proc_macro2::Span::call_site()),
+        None => proc_macro2::Ident::new(ident, proc_macro2::Span::call_site()),
+    }
+}
+
+/// Splits name into words at separators and case boundaries.
+/// - `createdAt` -> `created`, `At`
+/// - `created-at` -> `created`, `at`
+/// - `HTTPCode` -> `HTTP`, `Code`
+fn words(name: &str) -> Vec<String> {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut z = Vec::<String>::new();
+    let mut word = String::new();
+    for (i,&c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !word.is_empty() { z.push(std::mem::take(&mut word)) }
+            continue;
+        }
+        let prev = if i == 0 { None } else { chars.get(i-1) };
+        let next = chars.get(i+1);
+        let boundary = match prev {
+            Some(p) if c.is_ascii_uppercase() => p.is_ascii_lowercase() || p.is_ascii_digit()
+                || (p.is_ascii_uppercase() && next.map(|x| x.is_ascii_lowercase()).unwrap_or(false)),
+            _ => false,
+        };
+        if boundary && !word.is_empty() { z.push(std::mem::take(&mut word)) }
+        word.push(c);
+    }
+    if !word.is_empty() { z.push(word) }
+    z
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(x) => x.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase(),
+    }
+}
+
+/// Prefixes names starting with digit or empty names.
+fn prefix_digit(name: String, prefix: &str) -> String {
+    match name.chars().next() {
+        Some(x) if !x.is_ascii_digit() => name,
+        _ => format!("{}{}", prefix, name),
+    }
+}
+
+/// Escapes Rust keywords.
+/// Some keywords cannot be raw identifiers, and they get `_` suffix instead.
+fn escape(name: String) -> String {
+    match name.as_str() {
+        "self" | "Self" | "super" | "crate" | "_" => format!("{}_", name),
+        x if KEYWORDS.contains(&x) => format!("r#{}", name),
+        _ => name,
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "break", "const", "continue", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "async", "await", "dyn", "abstract", "become", "box", "do", "final", "macro",
+    "override", "priv", "typeof", "unsized", "virtual", "yield", "try", "gen",
+];
@@ -0,0 +1,80 @@
+//! Lint over Rust code-gen options.
+//! Options come from command line, configuration files and build scripts, and code-gen trusts them after this check.
+
+use crate::lint::*;
+use crate::model::Doc1;
+use crate::model::message::KType;
+use super::Options;
+use super::codegen::{FORMATS, FORMAT_PRESETS};
+use super::ident::{unraw, camel_case_ident, snake_case_ident};
+
+impl Lint for Options {
+    fn lint(&self, path: Path, context: &mut Context) {
+        for x in self.derives.iter() {
+            if syn::parse_str::<syn::Path>(x).is_err() {
+                context.error(path.appending("derive").appending(x), "must be a valid trait path");
+            }
+        }
+        for (format, r#type) in self.format_types.iter() {
+            if !FORMATS.contains(&format.as_str()) {
+                context.error(path.appending("format-types").appending(format), "must be a string format supported by KCG");
+            }
+            if syn::parse_str::<syn::Type>(r#type).is_err() {
+                context.error(path.appending("format-types").appending(format), "must be a valid Rust type expression");
+            }
+            if let Some((_,formats)) = FORMAT_PRESETS.iter().find(|(x,_)| x == r#type) {
+                if !formats.contains(&format.as_str()) {
+                    context.error(path.appending("format-types").appending(format), "preset is not available for this format");
+                }
+            }
+        }
+    }
+}
+
+impl Options {
+    /// Reports types named same with support items generated by these options.
+    /// - `--validate`: `Validate`, `ValidationError` and `ValidationErrors`.
+    /// - `byte=base64`: `Base64Bytes`.
+    /// - `--builders`: `{Name}Builder` for each product type.
+    pub fn lint_reserved_names(&self, doc: &Doc1, path: Path, context: &mut Context) {
+        let path = path.appending("types");
+        let mut reserved = Vec::<String>::new();
+        if self.validation {
+            reserved.extend(["Validate", "ValidationError", "ValidationErrors"].map(String::from));
+        }
+        if self.format_types.iter().any(|(k,v)| k == "byte" && v == "base64") {
+            reserved.push("Base64Bytes".to_string());
+        }
+        if self.builders {
+            for x in doc.types.iter() {
+                if let KType::Prod(x) = x {
+                    reserved.push(format!("{}Builder", unraw(&x.name)));
+                }
+            }
+        }
+        for x in doc.types.iter() {
+            if reserved.iter().any(|name| name == unraw(x.name())) {
+                context.error(path.appending(x.name()), "type name collides with support item generated by code-gen options");
+            }
+        }
+    }
+}
+
+/// Reports members mapped to same Rust identifier.
+/// Different names can be mapped to same identifier. (e.g. `createdAt` and `created-at`)
+pub fn lint_member_idents(doc: &Doc1, path: Path, context: &mut Context) {
+    let path = path.appending("types");
+    for x in doc.types.iter() {
+        let members = match x {
+            KType::New(_) => vec![],
+            KType::Enum(x) => x.cases.iter().map(|x| (x.name.as_str(), camel_case_ident(x.var_name.as_ref().unwrap_or(&x.name)))).collect(),
+            KType::Sum(x) => x.variants.iter().map(|x| (x.name.as_str(), camel_case_ident(&x.name))).collect(),
+            KType::Prod(x) => x.fields.iter().map(|x| (x.name.as_str(), snake_case_ident(&x.name))).collect(),
+        };
+        for (i,(name,ident)) in members.iter().enumerate() {
+            if members[..i].iter().any(|(_,x)| x == ident) {
+                context.error(path.appending(x.name()).appending(name), "member name collides with another member name in generated code");
+            }
+        }
+    }
+}
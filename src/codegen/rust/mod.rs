@@ -1,2 +1,10 @@
 mod codegen;
-mod test;
\ No newline at end of file
+mod builder;
+mod default;
+mod validation;
+mod ident;
+mod lint;
+mod test;
+
+pub use codegen::Options;
+pub use lint::lint_member_idents;
@@ -2,6 +2,7 @@
 
 use indoc::indoc;
 use crate::model::message::*;
+use crate::model::fixture::*;
 use crate::codegen::CodeGen;
 
 #[test]
@@ -11,19 +12,24 @@ fn enum_type_code() {
         cases: vec![
             KEnumTypeCase {
                 name: "Whale".to_string(),
+                var_name: None,
                 comment: "".to_string(),
+                deprecated: None,
             },
             KEnumTypeCase {
                 name: "Shrimp".to_string(),
+                var_name: None,
                 comment: "".to_string(),
+                deprecated: None,
             },
         ],
         comment: "".to_string(),
+        attrs: KTypeAttrs::default(),
     });
-    let b = a.code();
+    let b = a.code(&crate::codegen::rust::Options::default());
     assert_eq!(b.trim(), indoc!(r#"
-        #[derive(Serialize,Deserialize)]
-        #[derive(Eq,PartialEq)]
+        #[derive(Serialize, Deserialize)]
+        #[derive(Eq, PartialEq)]
         #[derive(Debug)]
         pub enum Fish {
             Whale,
@@ -57,33 +63,41 @@ fn sum_type_code() {
     let a = KType::Sum(KSumType { 
         name: "Pet".to_string(), 
         discriminant: "type".to_string(),
+        tagging: KSumTypeTagging::Internal,
         variants: vec![
             KSumTypeVariant {
                 name: "Cat".to_string(),
+                tag: "Cat".to_string(),
                 content: KContentStorage {
                     optional: false,
+                    nullable: false,
                     array: false,
                     r#type: KTypeRef::Def("Cat".to_string()),
                 },
                 comment: "".to_string(),
+                deprecated: None,
             },
             KSumTypeVariant {
                 name: "Dog".to_string(),
+                tag: "Dog".to_string(),
                 content: KContentStorage {
                     optional: false,
+                    nullable: false,
                     array: false,
                     r#type: KTypeRef::Def("Dog".to_string()),
                 },
                 comment: "".to_string(),
+                deprecated: None,
             },
         ], 
-        comment: "".to_string() });
-    let b = a.code();
+        comment: "".to_string(),
+        attrs: KTypeAttrs::default() });
+    let b = a.code(&crate::codegen::rust::Options::default());
     assert_eq!(b.trim(), indoc!(r#"
-        #[derive(Serialize,Deserialize)]
-        #[derive(Eq,PartialEq)]
+        #[derive(Serialize, Deserialize)]
+        #[derive(Eq, PartialEq)]
         #[derive(Debug)]
-        #[serde(tag="type")]
+        #[serde(tag = "type")]
         pub enum Pet {
             Cat(Cat),
             Dog(Dog),
@@ -91,6 +105,41 @@ fn sum_type_code() {
     "#).trim());
 }
 
+#[test]
+fn sum_type_renamed_variant_code() {
+    let a = KType::Sum(KSumType { 
+        name: "Pet".to_string(), 
+        discriminant: "type".to_string(),
+        tagging: KSumTypeTagging::Internal,
+        variants: vec![
+            KSumTypeVariant {
+                name: "Cat".to_string(),
+                tag: "cat".to_string(),
+                content: KContentStorage {
+                    optional: false,
+                    nullable: false,
+                    array: false,
+                    r#type: KTypeRef::Def("Cat".to_string()),
+                },
+                comment: "".to_string(),
+                deprecated: None,
+            },
+        ], 
+        comment: "".to_string(),
+        attrs: KTypeAttrs::default() });
+    let b = a.code(&crate::codegen::rust::Options::default());
+    assert_eq!(b.trim(), indoc!(r#"
+        #[derive(Serialize, Deserialize)]
+        #[derive(Eq, PartialEq)]
+        #[derive(Debug)]
+        #[serde(tag = "type")]
+        pub enum Pet {
+            #[serde(rename = "cat")]
+            Cat(Cat),
+        }
+    "#).trim());
+}
+
 #[test]
 fn prod_type_code() {
     let a = KType::Prod(KProdType { 
@@ -100,35 +149,48 @@ fn prod_type_code() {
                 name: "fuel".to_string(),
                 content: KContentStorage {
                     optional: true,
+                    nullable: false,
                     array: false,
                     r#type: KTypeRef::Prim(KPrimType::Bool),
                 },
+                constraints: KConstraints::default(),
+                default: None,
                 comment: "".to_string(),
+                deprecated: None,
             },
             KProdTypeField {
                 name: "cargo".to_string(),
                 content: KContentStorage {
                     optional: false,
+                    nullable: false,
                     array: false,
                     r#type: KTypeRef::Def("Cargo".to_string()),
                 },
+                constraints: KConstraints::default(),
+                default: None,
                 comment: "".to_string(),
+                deprecated: None,
             },
             KProdTypeField {
                 name: "crews".to_string(),
                 content: KContentStorage {
                     optional: false,
+                    nullable: false,
                     array: true,
                     r#type: KTypeRef::Prim(KPrimType::String),
                 },
+                constraints: KConstraints::default(),
+                default: None,
                 comment: "".to_string(),
+                deprecated: None,
             },
         ],
-        comment: "".to_string() });
-    let b = a.code();
+        comment: "".to_string(),
+        attrs: KTypeAttrs::default() });
+    let b = a.code(&crate::codegen::rust::Options::default());
     assert_eq!(b.trim(), indoc!("
-        #[derive(Serialize,Deserialize)]
-        #[derive(Eq,PartialEq)]
+        #[derive(Serialize, Deserialize)]
+        #[derive(Eq, PartialEq)]
         #[derive(Debug)]
         pub struct Ship {
             pub fuel: Option<bool>,
@@ -137,3 +199,808 @@ fn prod_type_code() {
         }
     ").trim());
 }
+
+/// Generated sum-type code compiled against sample variant types.
+/// Each module includes expected code-gen result to check serialized forms.
+mod sum_tagging {
+    use serde_derive::{Serialize,Deserialize};
+
+    #[derive(Serialize, Deserialize)]
+    #[derive(Eq, PartialEq)]
+    #[derive(Debug)]
+    pub struct Cat {
+        pub name: String,
+    }
+    #[derive(Serialize, Deserialize)]
+    #[derive(Eq, PartialEq)]
+    #[derive(Debug)]
+    pub struct Dog {
+        pub loud: bool,
+    }
+
+    pub mod internal { use super::*; include!("../../../tests/sum_tagging/internal.rs"); }
+    pub mod external { use super::*; include!("../../../tests/sum_tagging/external.rs"); }
+    pub mod adjacent { use super::*; include!("../../../tests/sum_tagging/adjacent.rs"); }
+    pub mod untagged { use super::*; include!("../../../tests/sum_tagging/untagged.rs"); }
+    pub mod unit { use super::*; include!("../../../tests/sum_tagging/unit.rs"); }
+}
+
+fn pet_sum_type(discriminant: &str, tagging: KSumTypeTagging) -> KType {
+    KType::Sum(KSumType { 
+        name: "Pet".to_string(), 
+        discriminant: discriminant.to_string(),
+        tagging,
+        variants: ["Cat", "Dog"].iter().map(|name| variant(name, def(name))).collect(),
+        comment: "".to_string(),
+        attrs: KTypeAttrs::default() })
+}
+
+fn assert_round_trip<T>(value: T, json: serde_json::Value) where T: serde::Serialize + serde::de::DeserializeOwned + Eq + std::fmt::Debug {
+    assert_eq!(serde_json::to_value(&value).unwrap(), json);
+    assert_eq!(serde_json::from_value::<T>(json).unwrap(), value);
+}
+
+#[test]
+fn sum_type_internal_tagging_round_trip() {
+    use sum_tagging::*;
+    let a = pet_sum_type("type", KSumTypeTagging::Internal);
+    assert_eq!(a.code(&crate::codegen::rust::Options::default()).trim(), include_str!("../../../tests/sum_tagging/internal.rs").trim());
+    assert_round_trip(
+        internal::Pet::Cat(Cat { name: "Tom".to_string() }),
+        serde_json::json!({ "type": "Cat", "name": "Tom" }));
+    assert_round_trip(
+        internal::Pet::Dog(Dog { loud: true }),
+        serde_json::json!({ "type": "Dog", "loud": true }));
+}
+
+#[test]
+fn sum_type_unit_variant_round_trip() {
+    use sum_tagging::*;
+    let a = KType::Sum(KSumType {
+        name: "Event".to_string(),
+        discriminant: "type".to_string(),
+        tagging: KSumTypeTagging::Internal,
+        variants: vec![
+            variant("Ping", KTypeRef::Unit),
+            variant("Cat", def("Cat")),
+        ],
+        comment: "".to_string(),
+        attrs: KTypeAttrs::default() });
+    assert_eq!(a.code(&crate::codegen::rust::Options::default()).trim(), include_str!("../../../tests/sum_tagging/unit.rs").trim());
+    assert_round_trip(
+        unit::Event::Ping,
+        serde_json::json!({ "type": "Ping" }));
+    assert_round_trip(
+        unit::Event::Cat(Cat { name: "Tom".to_string() }),
+        serde_json::json!({ "type": "Cat", "name": "Tom" }));
+}
+
+#[test]
+fn sum_type_external_tagging_round_trip() {
+    use sum_tagging::*;
+    let a = pet_sum_type("", KSumTypeTagging::External);
+    assert_eq!(a.code(&crate::codegen::rust::Options::default()).trim(), include_str!("../../../tests/sum_tagging/external.rs").trim());
+    assert_round_trip(
+        external::Pet::Cat(Cat { name: "Tom".to_string() }),
+        serde_json::json!({ "Cat": { "name": "Tom" } }));
+    assert_round_trip(
+        external::Pet::Dog(Dog { loud: true }),
+        serde_json::json!({ "Dog": { "loud": true } }));
+}
+
+#[test]
+fn sum_type_adjacent_tagging_round_trip() {
+    use sum_tagging::*;
+    let a = pet_sum_type("type", KSumTypeTagging::Adjacent { content: "value".to_string() });
+    assert_eq!(a.code(&crate::codegen::rust::Options::default()).trim(), include_str!("../../../tests/sum_tagging/adjacent.rs").trim());
+    assert_round_trip(
+        adjacent::Pet::Cat(Cat { name: "Tom".to_string() }),
+        serde_json::json!({ "type": "Cat", "value": { "name": "Tom" } }));
+    assert_round_trip(
+        adjacent::Pet::Dog(Dog { loud: true }),
+        serde_json::json!({ "type": "Dog", "value": { "loud": true } }));
+}
+
+#[test]
+fn sum_type_untagged_round_trip() {
+    use sum_tagging::*;
+    let a = pet_sum_type("", KSumTypeTagging::Untagged);
+    assert_eq!(a.code(&crate::codegen::rust::Options::default()).trim(), include_str!("../../../tests/sum_tagging/untagged.rs").trim());
+    assert_round_trip(
+        untagged::Pet::Cat(Cat { name: "Tom".to_string() }),
+        serde_json::json!({ "name": "Tom" }));
+    assert_round_trip(
+        untagged::Pet::Dog(Dog { loud: true }),
+        serde_json::json!({ "loud": true }));
+}
+
+#[test]
+fn type_attrs_code() {
+    let a = KType::Enum(KEnumType {
+        name: "Fish".to_string(),
+        cases: vec![],
+        comment: "".to_string(),
+        attrs: KTypeAttrs {
+            skip: false,
+            rust_type: None,
+            derive: vec!["Clone".to_string(), "Hash".to_string()],
+            deprecated: None,
+        },
+    });
+    assert!(a.code(&crate::codegen::rust::Options::default()).trim().starts_with(indoc!("
+        #[derive(Serialize, Deserialize)]
+        #[derive(Eq, PartialEq)]
+        #[derive(Debug)]
+        #[derive(Clone, Hash)]
+        pub enum Fish {
+    ").trim()));
+
+    let b = KType::New(KNewType {
+        name: "Order".to_string(),
+        origin: KTypeRef::Prim(KPrimType::String),
+        constraints: KConstraints::default(),
+        comment: "".to_string(),
+        attrs: KTypeAttrs {
+            skip: false,
+            rust_type: Some("uuid::Uuid".to_string()),
+            derive: vec![],
+            deprecated: None,
+        },
+    });
+    assert_eq!(b.code(&crate::codegen::rust::Options::default()).trim(), "pub type Order = uuid::Uuid;");
+
+    let c = crate::model::Doc1 {
+        funcs: vec![],
+        types: vec![KType::New(KNewType {
+            name: "Order".to_string(),
+            origin: KTypeRef::Prim(KPrimType::String),
+            constraints: KConstraints::default(),
+            comment: "".to_string(),
+            attrs: KTypeAttrs {
+                skip: true,
+                rust_type: None,
+                derive: vec![],
+                deprecated: None,
+            },
+        })],
+    };
+    assert!(!c.code(&crate::codegen::rust::Options::default()).contains("Order"));
+
+    // Documents keep aliases, so fields referencing aliased types compile.
+    let d = crate::model::Doc1 {
+        funcs: vec![],
+        types: vec![
+            b,
+            prod_type("Ship", vec![field("id", def("Order"))]),
+        ],
+    };
+    let e = d.code(&crate::codegen::rust::Options { builders: true, validation: true, ..Default::default() });
+    assert!(e.contains("pub type Order = uuid::Uuid;"));
+    assert!(e.contains("pub id: Order,"));
+    assert!(!e.contains("impl Validate for Order"));
+    assert!(!e.contains("OrderBuilder"));
+}
+
+#[test]
+fn ident_mapping() {
+    use super::ident::*;
+    assert_eq!(snake_case_ident("fuel"), "fuel");
+    assert_eq!(snake_case_ident("createdAt"), "created_at");
+    assert_eq!(snake_case_ident("created-at"), "created_at");
+    assert_eq!(snake_case_ident("HTTPCode"), "http_code");
+    assert_eq!(snake_case_ident("2fa"), "_2fa");
+    assert_eq!(snake_case_ident("type"), "r#type");
+    assert_eq!(snake_case_ident("self"), "self_");
+    assert_eq!(camel_case_ident("Whale"), "Whale");
+    assert_eq!(camel_case_ident("blue-whale"), "BlueWhale");
+    assert_eq!(camel_case_ident("BLUE_WHALE"), "BlueWhale");
+    assert_eq!(camel_case_ident("404"), "N404");
+    assert_eq!(camel_case_ident("self"), "Self_");
+}
+
+#[test]
+fn prod_type_renamed_field_code() {
+    let a = prod_type("User", ["type", "self", "created-at", "2fa"].iter().map(|name| field(name, prim(KPrimType::String))).collect());
+    let b = a.code(&crate::codegen::rust::Options::default());
+    assert_eq!(b.trim(), indoc!(r#"
+        #[derive(Serialize, Deserialize)]
+        #[derive(Eq, PartialEq)]
+        #[derive(Debug)]
+        pub struct User {
+            pub r#type: String,
+            #[serde(rename = "self")]
+            pub self_: String,
+            #[serde(rename = "created-at")]
+            pub created_at: String,
+            #[serde(rename = "2fa")]
+            pub _2fa: String,
+        }
+    "#).trim());
+}
+
+#[test]
+fn enum_type_renamed_case_code() {
+    let a = KType::Enum(KEnumType {
+        name: "Fish".to_string(),
+        cases: vec![
+            KEnumTypeCase {
+                name: "blue-whale".to_string(),
+                var_name: None,
+                comment: "".to_string(),
+                deprecated: None,
+            },
+        ],
+        comment: "".to_string(),
+        attrs: KTypeAttrs::default(),
+    });
+    let b = a.code(&crate::codegen::rust::Options::default());
+    assert!(b.contains(indoc!(r#"
+        pub enum Fish {
+            #[serde(rename = "blue-whale")]
+            BlueWhale,
+        }
+    "#)));
+    assert!(b.contains(r#""blue-whale" => Ok(BlueWhale),"#));
+    assert!(b.contains(r#"BlueWhale => "blue-whale".to_string(),"#));
+}
+
+#[test]
+fn enum_type_documented_code() {
+    let a = KType::Enum(KEnumType {
+        name: "Fish".to_string(),
+        cases: vec![
+            KEnumTypeCase {
+                name: "blue-whale".to_string(),
+                var_name: Some("BLUE_WHALE".to_string()),
+                comment: "Largest animal.".to_string(),
+                deprecated: None,
+            },
+            KEnumTypeCase {
+                name: "shrimp".to_string(),
+                var_name: Some("Prawn".to_string()),
+                comment: "".to_string(),
+                deprecated: None,
+            },
+        ],
+        comment: "Sea animals.".to_string(),
+        attrs: KTypeAttrs::default(),
+    });
+    let b = a.code(&crate::codegen::rust::Options::default());
+    assert!(b.starts_with("/// Sea animals.\n#[derive(Serialize, Deserialize)]"));
+    assert!(b.contains(indoc!(r#"
+        pub enum Fish {
+            /// Largest animal.
+            #[serde(rename = "blue-whale")]
+            BlueWhale,
+            #[serde(rename = "shrimp")]
+            Prawn,
+        }
+    "#)));
+    assert!(b.contains(r#""shrimp" => Ok(Prawn),"#));
+    assert!(b.contains(r#"Prawn => "shrimp".to_string(),"#));
+}
+
+#[test]
+fn recursive_type_code() {
+    let a = crate::model::Doc1 {
+        funcs: vec![],
+        types: vec![prod_type("Comment", vec![
+            field("parent", def("Comment")).optional(),
+            field("replies", def("Comment")).array(),
+        ])],
+    };
+    let b = a.code(&crate::codegen::rust::Options::default());
+    assert!(b.contains(indoc!("
+        pub struct Comment {
+            pub parent: Option<Box<Comment>>,
+            pub replies: Vec<Comment>,
+        }
+    ")));
+}
+
+// Enum code-gen implements `ToString` directly.
+#[allow(clippy::to_string_trait_impl)]
+mod default_derive {
+    include!("../../../tests/default_derive/ship.rs");
+}
+
+#[test]
+fn default_derive_code() {
+    let a = crate::model::Doc1 {
+        funcs: vec![],
+        types: vec![
+            KType::Enum(KEnumType {
+                name: "Fish".to_string(),
+                cases: vec![KEnumTypeCase { name: "Whale".to_string(), var_name: None, comment: "".to_string(), deprecated: None }],
+                comment: "".to_string(),
+                attrs: KTypeAttrs::default(),
+            }),
+            KType::Sum(KSumType {
+                name: "Pet".to_string(),
+                discriminant: "type".to_string(),
+                tagging: KSumTypeTagging::External,
+                variants: vec![variant("Cat", def("Cat"))],
+                comment: "".to_string(),
+                attrs: KTypeAttrs::default(),
+            }),
+            KType::New(KNewType {
+                name: "Code".to_string(),
+                origin: KTypeRef::Prim(KPrimType::String),
+                constraints: KConstraints { min_length: Some(1), ..Default::default() },
+                comment: "".to_string(),
+                attrs: KTypeAttrs::default(),
+            }),
+            prod_type("Cat", vec![field("name", prim(KPrimType::String))]),
+            prod_type("Ship", vec![field("fish", def("Fish")), field("pet", def("Pet")).optional()]),
+            prod_type("Fleet", vec![field("flagship", def("Ship"))]),
+            prod_type("Crew", vec![field("code", def("Code"))]),
+            prod_type("Tank", vec![field("fishes", def("Fish")).array(), field("pet", def("Pet")).optional(), field("cat", def("Cat"))]),
+        ],
+    };
+    let b = a.code(&crate::codegen::rust::Options { derives: vec!["Default".to_string()], ..Default::default() });
+    assert_eq!(b.trim(), include_str!("../../../tests/default_derive/ship.rs").trim());
+    // Only types without enum/sum-types and constrained new-types stored directly derive `Default`.
+    use default_derive::*;
+    assert_eq!(Tank::default(), Tank { fishes: vec![], pet: None, cat: Cat { name: "".to_string() } });
+    // Types without `Default` are still made by deserialization.
+    let c = serde_json::from_value::<Fleet>(serde_json::json!({ "flagship": { "fish": "Whale" } })).unwrap();
+    assert_eq!(c.flagship, Ship { fish: Fish::Whale, pet: None });
+    assert!(serde_json::from_value::<Crew>(serde_json::json!({ "code": "" })).is_err());
+}
+
+#[test]
+fn derive_options_code() {
+    let hashed = KTypeAttrs { derive: vec!["Hash".to_string()], ..Default::default() };
+    let a = crate::model::Doc1 {
+        funcs: vec![],
+        types: vec![
+            KType::Prod(KProdType { name: "Ship".to_string(), fields: vec![field("cargo", def("Cargo"))], attrs: hashed.clone(), ..Default::default() }),
+            prod_type("Cargo", vec![field("weight", prim(KPrimType::F64))]),
+            KType::Prod(KProdType { name: "Crew".to_string(), fields: vec![field("name", prim(KPrimType::String))], attrs: hashed, ..Default::default() }),
+        ],
+    };
+    let b = a.code(&crate::codegen::rust::Options {
+        skipping_type_names: vec![],
+        derives: vec!["Clone".to_string(), "Debug".to_string()],
+        ..Default::default()
+    });
+    assert!(b.contains(indoc!("
+        #[derive(Serialize, Deserialize)]
+        #[derive(PartialEq)]
+        #[derive(Debug)]
+        #[derive(Clone)]
+        pub struct Ship {
+    ")));
+    assert!(b.contains(indoc!("
+        #[derive(Serialize, Deserialize)]
+        #[derive(PartialEq)]
+        #[derive(Debug)]
+        #[derive(Clone)]
+        pub struct Cargo {
+    ")));
+    assert!(b.contains(indoc!("
+        #[derive(Serialize, Deserialize)]
+        #[derive(Eq, PartialEq)]
+        #[derive(Debug)]
+        #[derive(Clone, Hash)]
+        pub struct Crew {
+    ")));
+}
+
+mod builder {
+    include!("../../../tests/builder/ship.rs");
+}
+
+fn ship_doc() -> crate::model::Doc1 {
+    crate::model::Doc1 {
+        funcs: vec![],
+        types: vec![
+            prod_type("Ship", vec![
+                field("fuel", prim(KPrimType::Bool)).optional(),
+                field("name", prim(KPrimType::String)),
+                field("crews", prim(KPrimType::String)).array(),
+            ]),
+        ],
+    }
+}
+
+#[test]
+fn prod_type_builder_code() {
+    let a = ship_doc().code(&crate::codegen::rust::Options {
+        builders: true,
+        ..Default::default()
+    });
+    assert_eq!(a.trim(), include_str!("../../../tests/builder/ship.rs").trim());
+    let b = builder::Ship::builder()
+        .name("Titanic".to_string())
+        .crews(vec!["Jack".to_string()])
+        .build()
+        .unwrap();
+    assert_eq!(b, builder::Ship { fuel: None, name: "Titanic".to_string(), crews: vec!["Jack".to_string()] });
+    let c = builder::Ship::builder().fuel(true).build();
+    assert_eq!(c, Err(vec!["name", "crews"]));
+}
+
+mod validation {
+    include!("../../../tests/validation/ship.rs");
+}
+
+fn validated_doc() -> crate::model::Doc1 {
+    crate::model::Doc1 {
+        funcs: vec![],
+        types: vec![
+            prod_type("Ship", vec![
+                KProdTypeField {
+                    constraints: KConstraints { min_length: Some(1), pattern: Some("^[A-Z]".to_string()), ..KConstraints::default() },
+                    ..field("name", prim(KPrimType::String))
+                },
+                KProdTypeField {
+                    constraints: KConstraints { max_length: Some(8), max_items: Some(3), unique_items: true, ..KConstraints::default() },
+                    ..field("crews", prim(KPrimType::String)).array()
+                },
+                KProdTypeField {
+                    constraints: KConstraints { minimum: Some(0.into()), maximum: Some(100.into()), ..KConstraints::default() },
+                    ..field("fuel", prim(KPrimType::I32)).optional()
+                },
+                field("code", def("Code")),
+            ]),
+            KType::New(KNewType {
+                name: "Code".to_string(),
+                origin: KTypeRef::Prim(KPrimType::String),
+                constraints: KConstraints {
+                    pattern: Some("^[0-9]+$".to_string()),
+                    ..KConstraints::default()
+                },
+                comment: "".to_string(),
+                attrs: KTypeAttrs::default(),
+            }),
+            KType::Sum(KSumType {
+                name: "Fleet".to_string(),
+                discriminant: "".to_string(),
+                tagging: KSumTypeTagging::External,
+                variants: vec![KSumTypeVariant {
+                    tag: "ship".to_string(),
+                    content: KContentStorage { array: true, r#type: def("Ship"), ..Default::default() },
+                    ..variant("Ship", def("Ship"))
+                }],
+                comment: "".to_string(),
+                attrs: KTypeAttrs::default(),
+            }),
+        ],
+    }
+}
+
+#[test]
+fn validation_code() {
+    let a = validated_doc().code(&crate::codegen::rust::Options {
+        validation: true,
+        ..Default::default()
+    });
+    assert_eq!(a.trim(), include_str!("../../../tests/validation/ship.rs").trim());
+    use validation::*;
+    let ship = |name: &str, crews: &[&str], fuel: Option<i32>, code: &str| Ship {
+        name: name.to_string(),
+        crews: crews.iter().map(|x| x.to_string()).collect(),
+        fuel,
+        code: Code::try_from(code.to_string()).unwrap(),
+    };
+    assert_eq!(ship("Titanic", &["Jack", "Rose"], Some(50), "401").validate(), Ok(()));
+    let b = Fleet::Ship(vec![
+        ship("Titanic", &["Jack"], None, "401"),
+        ship("titanic", &["Jack", "Jack", "Cal", "Fabrizio De Rossi"], Some(101), "402"),
+    ]);
+    let paths = b.validate().unwrap_err().0.iter().map(|x| x.path.clone()).collect::<Vec<_>>();
+    assert_eq!(paths, vec![
+        "/ship/1/name",
+        "/ship/1/crews",
+        "/ship/1/crews",
+        "/ship/1/crews/3",
+        "/ship/1/fuel",
+    ]);
+}
+
+mod new_type {
+    use serde_derive::{Serialize,Deserialize};
+    include!("../../../tests/new_type/code.rs");
+}
+
+#[test]
+fn new_type_with_constraints_code() {
+    let a = KType::New(KNewType {
+        name: "Code".to_string(),
+        origin: KTypeRef::Prim(KPrimType::String),
+        constraints: KConstraints {
+            min_length: Some(2),
+            pattern: Some("^[0-9]+$".to_string()),
+            ..KConstraints::default()
+        },
+        comment: "Flight code.".to_string(),
+        attrs: KTypeAttrs {
+            skip: false,
+            rust_type: None,
+            derive: vec!["Default".to_string(), "Clone".to_string()],
+            deprecated: None,
+        },
+    });
+    assert_eq!(a.code(&crate::codegen::rust::Options::default()).trim(), include_str!("../../../tests/new_type/code.rs").trim());
+    use new_type::*;
+    let b = Code::try_from("401".to_string()).unwrap();
+    assert_eq!(b.as_str(), "401");
+    assert_eq!(serde_json::to_value(&b).unwrap(), serde_json::json!("401"));
+    assert_eq!(serde_json::from_value::<Code>(serde_json::json!("401")).unwrap(), b);
+    assert_eq!(Code::try_from("4".to_string()), Err("must be at least 2 characters long"));
+    assert_eq!(Code::try_from("A1".to_string()), Err("must match pattern `^[0-9]+$`"));
+    assert!(serde_json::from_value::<Code>(serde_json::json!("A1")).is_err());
+    assert_eq!(String::from(b), "401");
+}
+
+mod default {
+    include!("../../../tests/default/ship.rs");
+}
+
+#[test]
+fn prod_type_default_code() {
+    let a = crate::model::Doc1 {
+        funcs: vec![],
+        types: vec![prod_type("Ship", vec![
+            KProdTypeField { default: Some(serde_json::json!("Unnamed \"ship\"")), ..field("name", prim(KPrimType::String)) },
+            KProdTypeField { default: Some(serde_json::json!(1)), ..field("speed", prim(KPrimType::F64)) },
+            KProdTypeField { default: Some(serde_json::json!(["Jack", "Rose"])), ..field("crews", prim(KPrimType::String)).array() },
+            KProdTypeField { default: Some(serde_json::json!(100)), ..field("fuel", prim(KPrimType::I32)).optional() },
+            field("type", prim(KPrimType::Bool)).optional(),
+        ])],
+    };
+    let b = a.code(&crate::codegen::rust::Options {
+        derives: vec!["Default".to_string()],
+        ..Default::default()
+    });
+    assert_eq!(b.trim(), include_str!("../../../tests/default/ship.rs").trim());
+    use default::*;
+    let c = serde_json::from_value::<Ship>(serde_json::json!({})).unwrap();
+    assert_eq!(c, Ship::default());
+    assert_eq!(c, Ship {
+        name: "Unnamed \"ship\"".to_string(),
+        speed: 1.0,
+        crews: vec!["Jack".to_string(), "Rose".to_string()],
+        fuel: Some(100),
+        r#type: None,
+    });
+    let d = serde_json::from_value::<Ship>(serde_json::json!({ "name": "Titanic", "fuel": null })).unwrap();
+    assert_eq!(d.name, "Titanic");
+    assert_eq!(d.fuel, None);
+}
+
+mod formats {
+    include!("../../../tests/formats/ship.rs");
+}
+
+#[test]
+fn formatted_string_code() {
+    let a = crate::model::Doc1 {
+        funcs: vec![],
+        types: vec![
+            KType::New(KNewType {
+                name: "Timestamp".to_string(),
+                origin: KTypeRef::Prim(KPrimType::DateTime),
+                constraints: KConstraints::default(),
+                comment: "".to_string(),
+                attrs: KTypeAttrs::default(),
+            }),
+            prod_type("Ship", vec![
+                field("id", prim(KPrimType::Uuid)),
+                field("launched", prim(KPrimType::Date)),
+                field("updated", def("Timestamp")),
+                field("home", prim(KPrimType::Uri)).optional(),
+                field("photos", prim(KPrimType::Byte)).array(),
+            ]),
+        ],
+    };
+    // Presets are available only for matching formats.
+    let b = crate::codegen::rust::Options {
+        format_types: ["date-time", "date", "uuid", "byte"].iter().map(|x| (x.to_string(), "chrono".to_string())).collect(),
+        ..Default::default()
+    };
+    let mut x = crate::lint::Context::default();
+    crate::lint::Lint::lint(&b, crate::lint::Path::default(), &mut x);
+    let paths = x.logs.iter().filter(|x| x.severity == crate::lint::Severity::Error).map(|x| x.path.to_string()).collect::<Vec<_>>();
+    assert_eq!(paths, vec!["format-types/uuid", "format-types/byte"]);
+    // Unknown formats are rejected instead of being ignored silently.
+    let b = crate::codegen::rust::Options {
+        format_types: ["email", "date-tme", "uri"].iter().map(|x| (x.to_string(), "String".to_string())).collect(),
+        ..Default::default()
+    };
+    let mut x = crate::lint::Context::default();
+    crate::lint::Lint::lint(&b, crate::lint::Path::default(), &mut x);
+    let paths = x.logs.iter().filter(|x| x.severity == crate::lint::Severity::Error).map(|x| x.path.to_string()).collect::<Vec<_>>();
+    assert_eq!(paths, vec!["format-types/email", "format-types/date-tme"]);
+    let b = a.code(&crate::codegen::rust::Options {
+        format_types: vec![
+            ("date-time".to_string(), "chrono".to_string()),
+            ("date".to_string(), "chrono".to_string()),
+            ("uuid".to_string(), "uuid".to_string()),
+            ("byte".to_string(), "base64".to_string()),
+        ],
+        ..Default::default()
+    });
+    assert_eq!(b.trim(), include_str!("../../../tests/formats/ship.rs").trim());
+    let c = serde_json::json!({
+        "id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+        "launched": "1911-05-31",
+        "updated": "2022-01-02T03:04:05Z",
+        "home": "https://example.com/titanic",
+        "photos": ["AAEC"],
+    });
+    let d = serde_json::from_value::<formats::Ship>(c.clone()).unwrap();
+    assert_eq!(d.photos[0].0, vec![0, 1, 2]);
+    assert_eq!(d.launched.to_string(), "1911-05-31");
+    assert_eq!(serde_json::to_value(&d).unwrap(), c);
+}
+
+mod nullable {
+    include!("../../../tests/nullable/ship.rs");
+}
+
+#[test]
+fn nullable_field_code() {
+    let string = prim(KPrimType::String);
+    let a = crate::model::Doc1 {
+        funcs: vec![],
+        types: vec![
+            prod_type("Ship", vec![
+                field("name", string.clone()),
+                field("captain", string.clone()).nullable(),
+                field("flag", string.clone()).optional(),
+                field("port", string.clone()).optional().nullable(),
+                // A reference annotated as nullable with `allOf`.
+                field("cabin", def("Cabin")).optional().nullable(),
+            ]),
+            prod_type("Cabin", vec![field("number", prim(KPrimType::I32))]),
+        ],
+    };
+    let b = a.code(&crate::codegen::rust::Options::default());
+    assert_eq!(b.trim(), include_str!("../../../tests/nullable/ship.rs").trim());
+    // Absent, `null` and present values are distinguished.
+    let c = serde_json::from_value::<nullable::Ship>(serde_json::json!({ "name": "Titanic", "captain": null })).unwrap();
+    assert_eq!(c.captain, None);
+    assert_eq!(c.port, None);
+    assert_eq!(serde_json::to_value(&c).unwrap(), serde_json::json!({ "name": "Titanic", "captain": null, "flag": null }));
+    let c = serde_json::from_value::<nullable::Ship>(serde_json::json!({ "name": "Titanic", "captain": "Smith", "port": null })).unwrap();
+    assert_eq!(c.port, Some(None));
+    assert_eq!(serde_json::to_value(&c).unwrap(), serde_json::json!({ "name": "Titanic", "captain": "Smith", "flag": null, "port": null }));
+    let c = serde_json::from_value::<nullable::Ship>(serde_json::json!({ "name": "Titanic", "captain": "Smith", "port": "Southampton" })).unwrap();
+    assert_eq!(c.port, Some(Some("Southampton".to_string())));
+    let c = serde_json::from_value::<nullable::Ship>(serde_json::json!({ "name": "Titanic", "captain": null, "cabin": null })).unwrap();
+    assert_eq!(c.cabin, Some(None));
+    let c = serde_json::from_value::<nullable::Ship>(serde_json::json!({ "name": "Titanic", "captain": null, "cabin": { "number": 1 } })).unwrap();
+    assert_eq!(c.cabin, Some(Some(nullable::Cabin { number: 1 })));
+    assert_eq!(serde_json::to_value(&c).unwrap(), serde_json::json!({ "name": "Titanic", "captain": null, "flag": null, "cabin": { "number": 1 } }));
+}
+
+// Enum code-gen implements `ToString` directly.
+#[allow(clippy::to_string_trait_impl)]
+mod deprecated {
+    include!("../../../tests/deprecated/ship.rs");
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_code() {
+    let a = crate::model::Doc1 {
+        funcs: vec![],
+        types: vec![
+            KType::Enum(KEnumType {
+                name: "Flag".to_string(),
+                cases: ["Red", "Blue"].iter().map(|x| KEnumTypeCase {
+                    name: x.to_string(),
+                    var_name: None,
+                    comment: "".to_string(),
+                    deprecated: if *x == "Red" { Some("".to_string()) } else { None },
+                }).collect(),
+                comment: "".to_string(),
+                attrs: KTypeAttrs::default(),
+            }),
+            KType::Prod(KProdType {
+                name: "Cargo".to_string(),
+                fields: vec![field("weight", prim(KPrimType::I32))],
+                comment: "".to_string(),
+                attrs: KTypeAttrs { deprecated: Some("Use `Ship::load`.".to_string()), ..Default::default() },
+            }),
+            prod_type("Ship", vec![
+                field("name", prim(KPrimType::String)),
+                field("flag", def("Flag")),
+                KProdTypeField { deprecated: Some("".to_string()), ..field("cargo", def("Cargo")) },
+            ]),
+        ],
+    };
+    let b = a.code(&crate::codegen::rust::Options { builders: true, validation: true, ..Default::default() });
+    assert_eq!(b.trim(), include_str!("../../../tests/deprecated/ship.rs").trim());
+    // Generated code itself uses deprecated items without warnings.
+    use deprecated::*;
+    let cargo = Cargo::builder().weight(10).build().unwrap();
+    let ship = Ship::builder().name("Titanic".to_string()).flag("Red".parse().unwrap()).cargo(cargo).build().unwrap();
+    assert!(ship.validate().is_ok());
+    assert_eq!(ship.flag.to_string(), "Red");
+    let c = serde_json::to_value(&ship).unwrap();
+    assert_eq!(serde_json::from_value::<Ship>(c).unwrap(), ship);
+}
+
+#[test]
+fn generated_code_is_stable() {
+    let a = crate::model::Doc1 {
+        funcs: vec![],
+        types: vec![
+            prod_type("Ship", vec![
+                KProdTypeField {
+                    constraints: KConstraints { pattern: Some(r#"^"[a-z]\"#.to_string()), ..Default::default() },
+                    default: Some(serde_json::json!("say \"hi\"\n")),
+                    comment: "Ends block comments. */\n\n\\ and \"quotes\" are kept.".to_string(),
+                    deprecated: Some("Use \"kind\".".to_string()),
+                    ..field("type", prim(KPrimType::String)).optional()
+                },
+            ]),
+        ],
+    };
+    let b = a.code(&crate::codegen::rust::Options { builders: true, validation: true, ..Default::default() });
+    // Output is formatted with `prettyplease`, so formatting it again changes nothing.
+    // This is `prettyplease` style, which differs from `rustfmt` style.
+    let c = syn::parse_file(&b).unwrap();
+    assert_eq!(prettyplease::unparse(&c), b);
+    assert!(b.contains(indoc!(r#"
+        pub struct Ship {
+            /// Ends block comments. */
+            ///
+            /// \ and "quotes" are kept.
+            #[deprecated(note = "Use \"kind\".")]
+            #[serde(default = "Ship::default_type")]
+            pub r#type: Option<String>,
+        }
+    "#)));
+    assert!(b.contains(r#"Some("say \"hi\"\n".to_string())"#));
+}
+
+#[test]
+fn member_ident_lint() {
+    use crate::lint::*;
+    let mut a = ship_doc();
+    if let KType::Prod(x) = &mut a.types[0] {
+        for (x,name) in x.fields.iter_mut().zip(["createdAt", "created-at", "name"]) { x.name = name.to_string() }
+    }
+    let enum_type = |name: &str, cases: &[(&str,Option<&str>)]| KType::Enum(KEnumType {
+        name: name.to_string(),
+        cases: cases.iter().map(|(name,var_name)| KEnumTypeCase { name: name.to_string(), var_name: var_name.map(|x| x.to_string()), ..Default::default() }).collect(),
+        ..Default::default()
+    });
+    a.types.push(enum_type("Fish", &[("a-b", None), ("a_b", None), ("ab", None)]));
+    a.types.push(enum_type("Flag", &[("red", Some("Red")), ("blue", Some("Red"))]));
+    let mut x = Context::default();
+    crate::codegen::rust::lint_member_idents(&a, Path::default(), &mut x);
+    let paths = x.logs.iter().filter(|x| x.severity == Severity::Error).map(|x| x.path.to_string()).collect::<Vec<_>>();
+    assert_eq!(paths, vec!["types/Ship/created-at", "types/Fish/a_b", "types/Flag/blue"]);
+}
+
+#[test]
+fn reserved_name_lint() {
+    use crate::lint::*;
+    let mut a = ship_doc();
+    for name in ["ShipBuilder", "Validate", "ValidationErrors", "Base64Bytes"] {
+        a.types.push(KType::New(KNewType {
+            name: name.to_string(),
+            origin: KTypeRef::Prim(KPrimType::String),
+            constraints: KConstraints::default(),
+            comment: "".to_string(),
+            attrs: KTypeAttrs::default(),
+        }));
+    }
+    let paths = |options: &crate::codegen::rust::Options| {
+        let mut x = Context::default();
+        options.lint_reserved_names(&a, Path::default(), &mut x);
+        x.logs.iter().filter(|x| x.severity == Severity::Error).map(|x| x.path.to_string()).collect::<Vec<_>>()
+    };
+    assert!(paths(&crate::codegen::rust::Options::default()).is_empty());
+    assert_eq!(paths(&crate::codegen::rust::Options { builders: true, ..Default::default() }), vec!["types/ShipBuilder"]);
+    assert_eq!(paths(&crate::codegen::rust::Options { validation: true, ..Default::default() }), vec!["types/Validate", "types/ValidationErrors"]);
+    assert_eq!(paths(&crate::codegen::rust::Options {
+        format_types: vec![("byte".to_string(), "base64".to_string())],
+        ..Default::default()
+    }), vec!["types/Base64Bytes"]);
+}
@@ -0,0 +1,228 @@
+//! Validation code-gen.
+//! Generated product/sum-types implement `Validate` to check schema constraints at runtime.
+//! New-types with constraints check them on construction, so they don't implement `Validate`.
+
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use crate::model::*;
+use super::codegen::{float_code, int_code, some_code, Context};
+use super::ident::*;
+
+/// Makes `Validate` trait and error types shared by all generated types.
+pub(super) fn validation_support_code() -> TokenStream {
+    quote! {
+        /// A constraint violation found by `Validate`.
+        #[derive(Eq, PartialEq)]
+        #[derive(Debug)]
+        pub struct ValidationError {
+            /// JSON pointer to the violating value.
+            pub path: String,
+            pub message: String,
+        }
+        /// Constraint violations found by `Validate`.
+        #[derive(Eq, PartialEq)]
+        #[derive(Debug)]
+        pub struct ValidationErrors(pub Vec<ValidationError>);
+        impl std::fmt::Display for ValidationErrors {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                for x in self.0.iter() {
+                    writeln!(f, "{}: {}", x.path, x.message)?;
+                }
+                Ok(())
+            }
+        }
+        impl std::error::Error for ValidationErrors {}
+        /// Checks schema constraints which cannot be checked by type system.
+        pub trait Validate {
+            /// Collects violations into `errors`.
+            /// - `path`: JSON pointer to `self`.
+            fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>);
+            fn validate(&self) -> Result<(), ValidationErrors> {
+                let mut errors = Vec::new();
+                self.validate_at("", &mut errors);
+                match errors.is_empty() {
+                    true => Ok(()),
+                    false => Err(ValidationErrors(errors)),
+                }
+            }
+        }
+    }
+}
+
+impl message::KProdType {
+    pub(super) fn validation_code(&self, context: &Context) -> TokenStream {
+        let checks = self.fields.iter()
+            .map(|x| {
+                let ident = ident_code(&snake_case_ident(&x.name));
+                member_code(context, &x.content, &x.constraints, Some(quote!(&self.#ident)), Some(&x.name), false)
+            })
+            .filter(|x| !x.is_empty())
+            .map(|x| quote!({ #(#x)* }))
+            .collect::<Vec<_>>();
+        validate_impl_code(context, &self.name, checks)
+    }
+}
+
+impl message::KSumType {
+    pub(super) fn validation_code(&self, context: &Context) -> TokenStream {
+        use message::KSumTypeTagging::*;
+        let name = ident_code(&self.name);
+        let mut trivial = true;
+        let arms = self.variants.iter().map(|x| {
+            let segment = match &self.tagging {
+                External => Some(x.tag.as_str()),
+                Adjacent { content } => Some(content.as_str()),
+                Internal | Untagged => None,
+            };
+            let ident = ident_code(&camel_case_ident(&x.name));
+            if x.content.r#type == message::KTypeRef::Unit { return quote!(#name::#ident => (),) }
+            match member_code(context, &x.content, &message::KConstraints::default(), None, segment, true) {
+                code if code.is_empty() => quote!(#name::#ident(_) => (),),
+                code => {
+                    trivial = false;
+                    quote!(#name::#ident(value) => { #(#code)* },)
+                },
+            }
+        }).collect::<Vec<_>>();
+        let checks = match trivial {
+            true => vec![],
+            false => vec![quote!(match self { #(#arms)* })],
+        };
+        validate_impl_code(context, &self.name, checks)
+    }
+}
+
+fn validate_impl_code(context: &Context, name: &str, checks: Vec<TokenStream>) -> TokenStream {
+    let (path, errors) = match checks.is_empty() {
+        true => (quote!(_path), quote!(_errors)),
+        false => (quote!(path), quote!(errors)),
+    };
+    let allow = context.allow_deprecated_code();
+    let name = ident_code(name);
+    quote! {
+        #allow
+        impl Validate for #name {
+            fn validate_at(&self, #path: &str, #errors: &mut Vec<ValidationError>) {
+                #(#checks)*
+            }
+        }
+    }
+}
+
+/// Makes statements checking a member value.
+/// Returns no statement if there is nothing to check.
+/// - `value`: Expression of reference to member value. `None` if it's already bound to `value`.
+/// - `segment`: Serialized member name. `None` if member value is at same path with container.
+/// - `borrowed_path`: Whether `path` in scope is `&str`.
+fn member_code(context: &Context, storage: &message::KContentStorage, constraints: &message::KConstraints, value: Option<TokenStream>, segment: Option<&str>, borrowed_path: bool) -> Vec<TokenStream> {
+    let borrowed_path = borrowed_path && segment.is_none();
+    let mut lines = Vec::new();
+    match storage.array {
+        false => lines.extend(element_code(context, &storage.r#type, constraints, borrowed_path)),
+        true => {
+            lines.extend(array_code(constraints));
+            let element = element_code(context, &storage.r#type, constraints, false);
+            if !element.is_empty() {
+                lines.push(quote! {
+                    for (i, value) in value.iter().enumerate() {
+                        let path = format!("{}/{}", path, i);
+                        #(#element)*
+                    }
+                });
+            }
+        },
+    }
+    if lines.is_empty() { return lines }
+    lines = match (storage.option_depth(), value) {
+        (0, Some(value)) => [vec![quote!(let value = #value;)], lines].concat(),
+        (0, None) => lines,
+        (n, value) => {
+            let pattern = some_code(quote!(value), n);
+            let value = value.unwrap_or_else(|| quote!(value));
+            vec![quote!(if let #pattern = #value { #(#lines)* })]
+        },
+    };
+    if let Some(segment) = segment {
+        let segment = segment.replace('~', "~0").replace('/', "~1").replace('{', "{{").replace('}', "}}");
+        let format = format!("{{}}/{}", segment);
+        lines.insert(0, quote!(let path = format!(#format, path);));
+    }
+    lines
+}
+
+fn array_code(constraints: &message::KConstraints) -> Vec<TokenStream> {
+    let mut z = Vec::new();
+    if let Some(n) = constraints.min_items {
+        let limit = Literal::u64_unsuffixed(n);
+        z.push(check_code(quote!(value.len() < #limit), &format!("must have at least {} items", n)));
+    }
+    if let Some(n) = constraints.max_items {
+        let limit = Literal::u64_unsuffixed(n);
+        z.push(check_code(quote!(value.len() > #limit), &format!("must have at most {} items", n)));
+    }
+    if constraints.unique_items {
+        z.push(check_code(quote!((1..value.len()).any(|i| value[..i].contains(&value[i]))), "must have unique items"));
+    }
+    z
+}
+
+fn element_code(context: &Context, r#type: &message::KTypeRef, constraints: &message::KConstraints, borrowed_path: bool) -> Vec<TokenStream> {
+    use message::KTypeRef::*;
+    match r#type {
+        Unit => vec![],
+        Prim(x) => prim_check_code(x, constraints, &push_error_code),
+        // New-types with constraints are checked on construction.
+        Def(x) => match context.is_validated(x) {
+            true => {
+                let path = if borrowed_path { quote!(path) } else { quote!(&path) };
+                vec![quote!(value.validate_at(#path, errors);)]
+            },
+            false => vec![],
+        },
+    }
+}
+
+/// Makes statements checking a primitive value referenced by `value`.
+/// - `failure`: Makes a statement to run on violation from a message.
+pub(super) fn prim_check_code(r#type: &message::KPrimType, constraints: &message::KConstraints, failure: &dyn Fn(&str) -> TokenStream) -> Vec<TokenStream> {
+    use message::KPrimType::*;
+    let mut z = Vec::new();
+    let check = |condition: TokenStream, message: &str| {
+        let failure = failure(message);
+        quote!(if #condition { #failure })
+    };
+    let number = |n: &serde_json::Number| match (r#type, n.as_i64()) {
+        (I32 | I64, Some(x)) => (quote!(*value), int_code(x)),
+        (F64, _) => (quote!(*value), float_code(n.as_f64().unwrap_or_default())),
+        _ => (quote!((*value as f64)), float_code(n.as_f64().unwrap_or_default())),
+    };
+    if let Some(n) = constraints.minimum.as_ref() {
+        let (value, limit) = number(n);
+        z.push(check(quote!(#value < #limit), &format!("must be greater than or equal to {}", n)));
+    }
+    if let Some(n) = constraints.maximum.as_ref() {
+        let (value, limit) = number(n);
+        z.push(check(quote!(#value > #limit), &format!("must be less than or equal to {}", n)));
+    }
+    if let Some(n) = constraints.min_length {
+        let limit = Literal::u64_unsuffixed(n);
+        z.push(check(quote!(value.chars().count() < #limit), &format!("must be at least {} characters long", n)));
+    }
+    if let Some(n) = constraints.max_length {
+        let limit = Literal::u64_unsuffixed(n);
+        z.push(check(quote!(value.chars().count() > #limit), &format!("must be at most {} characters long", n)));
+    }
+    if let Some(x) = constraints.pattern.as_ref() {
+        // Compiled at each check to keep generated code free of global state.
+        z.push(check(quote!(!regex::Regex::new(#x).is_ok_and(|x| x.is_match(value))), &format!("must match pattern `{}`", x)));
+    }
+    z
+}
+
+fn check_code(condition: TokenStream, message: &str) -> TokenStream {
+    let failure = push_error_code(message);
+    quote!(if #condition { #failure })
+}
+fn push_error_code(message: &str) -> TokenStream {
+    quote!(errors.push(ValidationError { path: path.to_string(), message: #message.to_string() });)
+}
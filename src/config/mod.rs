@@ -0,0 +1,141 @@
+//! Project configuration file. (`kcg.toml`)
+//!
+//! Describes inputs and targets of a project, so KCG can run with no repeated arguments.
+//! Relative paths in a configuration file are relative to the directory of the file.
+
+mod test;
+
+use serde_derive::Deserialize;
+use crate::lint::{Context, Path, Severity, RULES};
+
+pub type Map<K,V> = linear_map::LinearMap<K,V>;
+
+/// Name of configuration file.
+pub const FILE_NAME: &str = "kcg.toml";
+
+#[derive(Deserialize)]
+#[derive(Eq,PartialEq)]
+#[derive(Default)]
+#[derive(Debug)]
+#[serde(deny_unknown_fields, rename_all="kebab-case")]
+pub struct Config {
+    /// Path to source OpenAPI 3.0 schema file.
+    pub input: Option<String>,
+    /// Reads `input` as KCG IR JSON file.
+    #[serde(default)]
+    pub ir: bool,
+    /// Prefix to rename types with names colliding with Rust names or other type names.
+    pub collision_prefix: Option<String>,
+    /// Suffix to rename types with names colliding with Rust names or other type names.
+    pub collision_suffix: Option<String>,
+    #[serde(default)]
+    pub targets: Targets,
+    /// Severity overrides of lint rules keyed by rule IDs. (`crate::lint::RULES`)
+    #[serde(default)]
+    pub lints: Map<String,LintLevel>,
+}
+
+/// Code-gen targets.
+/// Only Rust is supported for now.
+#[derive(Deserialize)]
+#[derive(Eq,PartialEq)]
+#[derive(Default)]
+#[derive(Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Targets {
+    pub rust: Option<RustTarget>,
+}
+
+#[derive(Deserialize)]
+#[derive(Eq,PartialEq)]
+#[derive(Default)]
+#[derive(Debug)]
+#[serde(deny_unknown_fields, rename_all="kebab-case")]
+pub struct RustTarget {
+    /// Path to write generated Rust code.
+    pub output: Option<String>,
+    /// Path to prefix code for generated code.
+    pub include: Option<String>,
+    /// Skipping type names.
+    #[serde(default)]
+    pub skip: Vec<String>,
+    /// Additional traits to derive on all generated types.
+    #[serde(default)]
+    pub derive: Vec<String>,
+    /// Generates builder types for product types.
+    #[serde(default)]
+    pub builder: bool,
+    /// Generates `Validate` implementations.
+    #[serde(default)]
+    pub validate: bool,
+    /// Rust types for string formats.
+    #[serde(default)]
+    pub format_types: Map<String,String>,
+}
+
+#[derive(Deserialize)]
+#[derive(Clone,Copy)]
+#[derive(Eq,PartialEq)]
+#[derive(Debug)]
+#[serde(rename_all="kebab-case")]
+pub enum LintLevel {
+    /// Drops logs.
+    Allow,
+    Info,
+    Warning,
+    Error,
+}
+
+impl Config {
+    /// Finds configuration file in `dir` or its ancestors.
+    pub fn discover(dir: &std::path::Path) -> Option<std::path::PathBuf> {
+        dir.ancestors().map(|x| x.join(FILE_NAME)).find(|x| x.is_file())
+    }
+    /// Reads configuration file at `path`.
+    /// Relative paths are resolved against the directory of the file.
+    pub fn read(path: &std::path::Path) -> Result<Config,Box<dyn std::error::Error>> {
+        let src = std::fs::read_to_string(path)?;
+        let mut config = toml::from_str::<Config>(&src)?;
+        config.rebase(path.parent().unwrap_or(std::path::Path::new("")));
+        Ok(config)
+    }
+    fn rebase(&mut self, dir: &std::path::Path) {
+        let rebase = |x: &mut Option<String>| {
+            if let Some(path) = x {
+                *path = dir.join(&path).to_string_lossy().into_owned();
+            }
+        };
+        rebase(&mut self.input);
+        if let Some(x) = &mut self.targets.rust {
+            rebase(&mut x.output);
+            rebase(&mut x.include);
+        }
+    }
+    /// Applies lint levels to logs in `context`.
+    /// Errors reject unsupported patterns, so they cannot be lowered.
+    pub fn adjust_lints(&self, context: &mut Context) {
+        adjust_lints(&self.lints, context);
+    }
+}
+
+/// Applies lint levels keyed by rule IDs to logs in `context`.
+/// Errors reject unsupported patterns, so they cannot be lowered.
+/// Unknown rule IDs are reported as errors, so stale configurations are not ignored silently.
+pub fn adjust_lints(lints: &Map<String,LintLevel>, context: &mut Context) {
+    context.logs.retain_mut(|x| {
+        let id = match (x.severity, x.rule) {
+            (Severity::Error, _) | (_, None) => return true,
+            (_, Some(id)) => id,
+        };
+        match lints.get(id) {
+            None => true,
+            Some(LintLevel::Allow) => false,
+            Some(LintLevel::Info) => { x.severity = Severity::Info; true },
+            Some(LintLevel::Warning) => { x.severity = Severity::Warning; true },
+            Some(LintLevel::Error) => { x.severity = Severity::Error; true },
+        }
+    });
+    for id in lints.keys().filter(|x| !RULES.iter().any(|rule| rule.id == x.as_str())) {
+        context.error(Path::default().appending("lints").appending(id), "unknown lint rule ID");
+    }
+}
@@ -0,0 +1,67 @@
+#![cfg(test)]
+
+use indoc::indoc;
+use crate::lint::*;
+use super::*;
+
+#[test]
+fn test_reading_config() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        input = "api1.yaml"
+        collision-suffix = "Type"
+
+        [targets.rust]
+        output = "src/impl1.rs"
+        skip = ["Ship"]
+        derive = ["Hash"]
+        builder = true
+        format-types = { date-time = "chrono" }
+
+        [lints]
+        type-unreferenced = "allow"
+        unknown-string-format = "error"
+    "#);
+    let mut b = toml::from_str::<Config>(a)?;
+    b.rebase(std::path::Path::new("proj"));
+    assert_eq!(b.input, Some(std::path::Path::new("proj").join("api1.yaml").to_string_lossy().into_owned()));
+    assert_eq!(b.collision_suffix, Some("Type".to_string()));
+    let rust = b.targets.rust.as_ref().unwrap();
+    assert_eq!(rust.output, Some(std::path::Path::new("proj").join("src/impl1.rs").to_string_lossy().into_owned()));
+    assert_eq!(rust.include, None);
+    assert_eq!(rust.skip, vec!["Ship".to_string()]);
+    assert!(rust.builder);
+    assert!(!rust.validate);
+    assert_eq!(rust.format_types.get("date-time"), Some(&"chrono".to_string()));
+
+    let mut x = Context::default();
+    x.warning(Path::default().appending("Ship"), TYPE_UNREFERENCED);
+    x.warning(Path::default().appending("Fish"), UNKNOWN_STRING_FORMAT);
+    x.info(Path::default().appending("Order"), SKIPPED_TYPE_REFERENCED);
+    x.error(Path::default().appending("Pet"), "type is not referenced by any other type");
+    b.adjust_lints(&mut x);
+    let logs = x.logs.iter().map(|x| (x.severity, x.path.to_string())).collect::<Vec<_>>();
+    assert_eq!(logs, vec![
+        (Severity::Error, "Fish".to_string()),
+        (Severity::Info, "Order".to_string()),
+        (Severity::Error, "Pet".to_string()),
+    ]);
+
+    // Levels are keyed by rule IDs, and unknown IDs are errors.
+    let c = toml::from_str::<Config>("[lints]\n\"type is not referenced by any other type\" = \"allow\"")?;
+    let mut x = Context::default();
+    x.warning(Path::default().appending("Ship"), TYPE_UNREFERENCED);
+    c.adjust_lints(&mut x);
+    let logs = x.logs.iter().map(|x| (x.severity, x.path.to_string())).collect::<Vec<_>>();
+    assert_eq!(logs, vec![
+        (Severity::Warning, "Ship".to_string()),
+        (Severity::Error, "lints/type is not referenced by any other type".to_string()),
+    ]);
+    Ok(())
+}
+
+#[test]
+fn test_reading_config_fail() {
+    assert!(toml::from_str::<Config>("[targets.typescript]\noutput = \"a.ts\"").is_err());
+    assert!(toml::from_str::<Config>("[lints]\n\"x\" = \"deny\"").is_err());
+    assert!(toml::from_str::<Config>("inputs = \"a.yaml\"").is_err());
+}
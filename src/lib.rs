@@ -2,4 +2,8 @@ pub mod model;
 pub mod scan;
 pub mod lint;
 pub mod codegen;
-mod util;
\ No newline at end of file
+pub mod config;
+pub mod build;
+mod util;
+
+pub use build::Builder;
@@ -15,10 +15,31 @@ pub struct Context {
 impl Context {
     /// Records an error log.
     pub fn error(self: &mut Self, path: Path, message: &'static str) {
+        self.log(Severity::Error, path, message)
+    }
+    /// Records a warning log.
+    /// Warnings do not reject input, and their severities can be configured by rule IDs.
+    pub fn warning(&mut self, path: Path, rule: Rule) {
+        self.log_rule(Severity::Warning, path, rule)
+    }
+    /// Records an informational log.
+    pub fn info(&mut self, path: Path, rule: Rule) {
+        self.log_rule(Severity::Info, path, rule)
+    }
+    fn log(&mut self, severity: Severity, path: Path, message: &'static str) {
         self.logs.push(Log {
-            severity: Severity::Error,
-            path: path.clone(),
+            severity,
+            path,
             message: RCString::from(message.to_owned()),
+            rule: None,
+        })
+    }
+    fn log_rule(&mut self, severity: Severity, path: Path, rule: Rule) {
+        self.logs.push(Log {
+            severity,
+            path,
+            message: RCString::from(rule.message.to_owned()),
+            rule: Some(rule.id),
         })
     }
 }
@@ -47,8 +68,9 @@ impl std::fmt::Display for Context {
     }
 }
 
+/// Input is rejected by lint errors.
 #[derive(Debug)]
-struct LintError(Vec<Log>);
+pub struct LintError(pub Vec<Log>);
 impl std::fmt::Display for LintError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> { 
         for x in self.0.iter() {
@@ -103,20 +125,43 @@ pub struct Log {
     pub severity: Severity,
     pub path: Path,
     pub message: RCString,
+    /// ID of the rule for configurable logs.
+    pub rule: Option<&'static str>,
 }
 impl std::fmt::Display for Log {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> { 
         f.write_fmt(format_args!("{:#?}({}): {}", self.severity, self.path, self.message))?;
+        if let Some(x) = self.rule {
+            f.write_fmt(format_args!(" [{}]", x))?;
+        }
         Ok(())
     }
 }
 
+/// Lint rule which does not reject input by default.
+/// Severities of these rules are configured by their IDs, and IDs are kept even if messages are reworded.
+#[derive(Clone,Copy)]
+#[derive(Eq,PartialEq)]
+#[derive(Debug)]
+pub struct Rule {
+    pub id: &'static str,
+    pub message: &'static str,
+}
+pub const SKIPPED_TYPE_UNDEFINED: Rule = Rule { id: "skipped-type-undefined", message: "skipped type is not defined" };
+pub const SKIPPED_TYPE_REFERENCED: Rule = Rule { id: "skipped-type-referenced", message: "references skipped type (prelude must provide it)" };
+pub const TYPE_UNREFERENCED: Rule = Rule { id: "type-unreferenced", message: "type is not referenced by any other type" };
+pub const UNKNOWN_STRING_FORMAT: Rule = Rule { id: "unknown-string-format", message: "unknown string format (treated as plain string)" };
+/// All configurable rules.
+pub const RULES: &[Rule] = &[SKIPPED_TYPE_UNDEFINED, SKIPPED_TYPE_REFERENCED, TYPE_UNREFERENCED, UNKNOWN_STRING_FORMAT];
+
+/// Severities in ascending order.
 #[derive(Clone,Copy)]
 #[derive(Eq,PartialEq)]
+#[derive(Ord,PartialOrd)]
 #[derive(Debug)]
 pub enum Severity {
-    // Info,
-    // Warning,
+    Info,
+    Warning,
     Error,
 }
 pub type RCString = std::rc::Rc<String>;
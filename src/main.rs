@@ -3,6 +3,11 @@ mod scan;
 mod lint;
 mod codegen;
 mod util;
+mod config;
+// Only part of build script API is used by command line tool.
+#[allow(dead_code)]
+mod build;
+mod test;
 
 use structopt::StructOpt;
 use lint::Lint;
@@ -10,64 +15,411 @@ use lint::Lint;
 #[derive(StructOpt)]
 #[structopt(name="kcg", about="A Schema Code-Gen.")]
 struct Opt {
+    /// Prints only errors.
+    #[structopt(long="quiet", short="q", global=true)]
+    quiet: bool,
+    /// Prints informational logs and progress too.
+    #[structopt(long="verbose", short="v", global=true, conflicts_with="quiet")]
+    verbose: bool,
+    /// Path to configuration file.
+    /// KCG finds `kcg.toml` from current directory upward if this is not designated.
+    /// Command line options override configured values.
+    #[structopt(long="config", global=true)]
+    config: Option<String>,
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(StructOpt)]
+enum Command {
+    /// Checks a schema without generating code.
+    Lint(LintOpt),
+    /// Generates Rust code from a schema.
+    Gen(GenOpt),
+    /// Classifies changes between two schema versions.
+    /// Fails if there's any breaking change.
+    Diff(DiffOpt),
+    /// Rewrites a schema file in canonical YAML form.
+    Fmt(FmtOpt),
+    /// Writes scanned model as KCG IR JSON.
+    #[structopt(alias="scan")]
+    Ir(IrOpt),
+}
+
+/// Options to read a model. Shared by `kcg lint` and `kcg gen`.
+#[derive(StructOpt)]
+struct ModelOpt {
     /// Path to source OpenAPI 3.0 schema file.
     /// Please note that only certain subset will be supported.
-    input: String,
+    /// Configured `input` is used if this is not designated.
+    input: Option<String>,
+    /// Reads `input` as KCG IR JSON file made by `kcg ir`.
+    #[structopt(long="ir")]
+    ir: bool,
+    /// Skipping type names.
+    /// KCG won't make code for types with names in `skippings`.
+    /// You are supposed to provide type definitions yourself using <prelude> option.
+    #[structopt(long="skip")]
+    skippings: Vec<String>,
+    /// Prefix to rename types with names colliding with Rust names or other type names.
+    #[structopt(long="collision-prefix")]
+    collision_prefix: Option<String>,
+    /// Suffix to rename types with names colliding with Rust names or other type names.
+    #[structopt(long="collision-suffix")]
+    collision_suffix: Option<String>,
+}
+
+/// Options of `kcg lint`.
+#[derive(StructOpt)]
+struct LintOpt {
+    #[structopt(flatten)]
+    model: ModelOpt,
+}
+
+/// Options of `kcg gen`.
+#[derive(StructOpt)]
+struct GenOpt {
+    #[structopt(flatten)]
+    model: ModelOpt,
     /// Path to write generated Rust code.
-    /// KCG won't produce target code if this is not designated.
-    /// Then effectively performs only lint stage.
+    /// Writes to standard output if this is not designated.
     output: Option<String>,
 
     /// Prefix code for generated code.
     #[structopt(long="include")]
     prelude: Option<String>,
-    /// Skipping type names.
-    /// KCG won't make code for types with names in `skippings`.
-    /// You are supposed to provide type definitions yourself using <prelude> option.
-    #[structopt(long="skip")]
-    skippings: Vec<String>,
+    /// Additional traits to derive on all generated types.
+    /// KCG drops `Eq`, `Ord` and `Hash` for types containing floating-point numbers.
+    #[structopt(long="derive")]
+    derives: Vec<String>,
+    /// Generates builder types for product types.
+    #[structopt(long="builder")]
+    builders: bool,
+    /// Generates no builder types even if configured.
+    #[structopt(long="no-builder", conflicts_with="builders")]
+    no_builders: bool,
+    /// Generates `Validate` implementations checking schema constraints at runtime.
+    #[structopt(long="validate")]
+    validation: bool,
+    /// Generates no `Validate` implementations even if configured.
+    #[structopt(long="no-validate", conflicts_with="validation")]
+    no_validation: bool,
+    /// Rust type for strings with a `format` in `<format>=<type>` form.
+    /// Strings with other formats are made as `String`.
+    /// Presets `chrono`, `uuid`, `url` and `base64` are available for
+    /// `date-time`/`date`, `uuid`, `uri` and `byte` formats.
+    #[structopt(long="format-type", parse(try_from_str=parse_format_type))]
+    format_types: Vec<(String,String)>,
+}
+
+/// Options of `kcg diff`.
+#[derive(StructOpt)]
+struct DiffOpt {
+    /// Path to old OpenAPI 3.0 schema file.
+    old: String,
+    /// Path to new OpenAPI 3.0 schema file.
+    /// Fails if there's any breaking change from `old`.
+    new: String,
+}
+
+/// Options of `kcg fmt`.
+#[derive(StructOpt)]
+struct FmtOpt {
+    /// Path to source OpenAPI 3.0 schema file.
+    input: String,
+    /// Path to write formatted schema.
+    /// Writes to standard output if this is not designated.
+    /// Please note that YAML comments are not preserved.
+    output: Option<String>,
+    /// Writes nothing, and fails if `input` is not formatted.
+    #[structopt(long="check")]
+    check: bool,
+}
+
+/// Options of `kcg ir`.
+#[derive(StructOpt)]
+struct IrOpt {
+    /// Path to source OpenAPI 3.0 schema file.
+    input: String,
+    /// Path to write scanned model.
+    /// Writes to standard output if this is not designated.
+    output: Option<String>,
+    /// Format of scanned model. Only `ir` (KCG IR JSON) is supported.
+    #[structopt(long="emit", default_value="ir", possible_values=&["ir"])]
+    emit: String,
+}
+
+/// Reasons of failure. Each reason exits with distinct code.
+#[derive(Debug)]
+enum Failure {
+    /// Input is rejected. (lint errors, breaking changes or unformatted schema)
+    Rejected(String),
+    /// Failed to parse arguments, or to read/parse/write files.
+    Io(Box<dyn std::error::Error>),
+}
+impl Failure {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Failure::Rejected(_) => 1,
+            Failure::Io(_) => 2,
+        }
+    }
+}
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Failure::Rejected(x) => write!(f, "error: {}", x),
+            Failure::Io(x) => write!(f, "error: {}", x),
+        }
+    }
+}
+impl<E:Into<Box<dyn std::error::Error>>> From<E> for Failure {
+    fn from(x: E) -> Failure {
+        Failure::Io(x.into())
+    }
 }
+type Result<T> = std::result::Result<T,Failure>;
+
+/// Exit code for internal errors. (panics)
+const INTERNAL_ERROR_EXIT_CODE: i32 = 3;
 
 fn main() {
-    match run() {
-        Ok(_) => std::process::exit(0),
-        Err(x) => {
-            println!("{}", x);
-            std::process::exit(1);
+    let opt = match Opt::from_args_safe() {
+        Ok(x) => x,
+        Err(x) if x.use_stderr() => {
+            eprintln!("{}", x.message);
+            std::process::exit(Failure::Io(x.into()).exit_code());
+        },
+        // Help or version.
+        Err(x) => x.exit(),
+    };
+    // Panic message is printed by default panic hook.
+    match std::panic::catch_unwind(|| run(opt)) {
+        Ok(Ok(_)) => std::process::exit(0),
+        Ok(Err(x)) => {
+            eprintln!("{}", x);
+            std::process::exit(x.exit_code());
+        },
+        Err(_) => std::process::exit(INTERNAL_ERROR_EXIT_CODE),
+    }
+}
+fn run(mut opt: Opt) -> Result<()> {
+    let config = read_config(&opt.config)?;
+    match &mut opt.command {
+        Command::Lint(x) => x.model.merge(&config),
+        Command::Gen(x) => x.merge(&config),
+        _ => (),
+    }
+    let log = Logger {
+        level: match (opt.quiet, opt.verbose) {
+            (true, _) => lint::Severity::Error,
+            (_, true) => lint::Severity::Info,
+            _ => lint::Severity::Warning,
         },
+        config,
+    };
+    match opt.command {
+        Command::Lint(x) => {
+            let k = model_builder(&x.model, &log)?.read_model_reporting(&mut |x| log.print(x)).map_err(builder_failure)?;
+            log.progress(&format!("read {} type(s)", k.types.len()));
+            Ok(())
+        },
+        Command::Gen(x) => run_gen(x, &log),
+        Command::Diff(x) => run_diff(x, &log),
+        Command::Fmt(x) => run_fmt(x, &log),
+        Command::Ir(x) => run_ir(x, &log),
+    }
+}
+
+/// Reads configuration file at `path`, or discovered one if `path` is `None`.
+/// Returns default configuration if there's no configuration file.
+fn read_config(path: &Option<String>) -> Result<config::Config> {
+    let path = match path {
+        Some(x) => std::path::PathBuf::from(x),
+        None => match config::Config::discover(&std::env::current_dir()?) {
+            Some(x) => x,
+            None => return Ok(config::Config::default()),
+        },
+    };
+    config::Config::read(&path).map_err(|e| Failure::from(format!("cannot read `{}`: {}", path.display(), e)))
+}
+
+impl ModelOpt {
+    /// Fills options missing in command line with configured values.
+    fn merge(&mut self, config: &config::Config) {
+        if self.input.is_none() {
+            self.input = config.input.clone();
+            self.ir |= config.ir;
+        }
+        if self.collision_prefix.is_none() { self.collision_prefix = config.collision_prefix.clone() }
+        if self.collision_suffix.is_none() { self.collision_suffix = config.collision_suffix.clone() }
+        if let Some(rust) = &config.targets.rust {
+            if self.skippings.is_empty() { self.skippings = rust.skip.clone() }
+        }
+    }
+}
+
+impl GenOpt {
+    /// Fills options missing in command line with configured values.
+    /// Format types are merged by formats.
+    fn merge(&mut self, config: &config::Config) {
+        self.model.merge(config);
+        if let Some(rust) = &config.targets.rust {
+            if self.output.is_none() { self.output = rust.output.clone() }
+            if self.prelude.is_none() { self.prelude = rust.include.clone() }
+            if self.derives.is_empty() { self.derives = rust.derive.clone() }
+            // `--no-*` flags override configured `true`.
+            self.builders |= rust.builder && !self.no_builders;
+            self.validation |= rust.validate && !self.no_validation;
+            let mut format_types = rust.format_types.iter()
+                .filter(|(k,_)| !self.format_types.iter().any(|(x,_)| x == *k))
+                .map(|(k,v)| (k.clone(), v.clone()))
+                .collect::<Vec<_>>();
+            format_types.append(&mut self.format_types);
+            self.format_types = format_types;
+        }
+    }
+}
+
+fn run_gen(opt: GenOpt, log: &Logger) -> Result<()> {
+    let mut b = model_builder(&opt.model, log)?
+        .builders(opt.builders)
+        .validation(opt.validation);
+    if let Some(x) = opt.prelude.as_ref() { b = b.include(x) }
+    for x in opt.derives.iter() { b = b.derive(x) }
+    for (format,r#type) in opt.format_types.iter() { b = b.format_type(format, r#type) }
+    let code = b.generate_reporting(&mut |x| log.print(x)).map_err(builder_failure)?;
+    write_output(&opt.output, &code, log)
+}
+
+/// Makes a builder reading a model with options.
+/// `kcg lint` and `kcg gen` run through `Builder`, so they behave same with build scripts.
+fn model_builder(opt: &ModelOpt, log: &Logger) -> Result<build::Builder> {
+    let input = opt.input.as_deref().ok_or("no input schema (designate it or configure `input` in `kcg.toml`)")?;
+    let mut b = build::Builder::new()
+        .cargo_metadata(false)
+        .input(input)
+        .ir(opt.ir)
+        .collision_prefix(opt.collision_prefix.as_deref().unwrap_or_default())
+        .collision_suffix(opt.collision_suffix.as_deref().unwrap_or_default());
+    for x in opt.skippings.iter() { b = b.skip(x) }
+    for (rule,level) in log.config.lints.iter() { b = b.lint_level(rule, *level) }
+    Ok(b)
+}
+
+/// Classifies errors of `Builder`. Lint and scan errors reject input.
+fn builder_failure(e: Box<dyn std::error::Error>) -> Failure {
+    let e = match e.downcast::<lint::LintError>() {
+        Ok(x) => return Failure::Rejected(format!("{} lint error(s) found", x.0.len())),
+        Err(e) => e,
+    };
+    match e.downcast::<build::Rejected>() {
+        Ok(x) => Failure::Rejected(x.0),
+        Err(e) => Failure::Io(e),
     }
 }
-fn run() -> Result<(),Box<dyn std::error::Error>> {
-    let opt = Opt::from_args();
-    let src = std::fs::read_to_string(opt.input)?;
+
+fn run_ir(opt: IrOpt, log: &Logger) -> Result<()> {
+    let k = read_model(&opt.input, log)?;
+    let text = match opt.emit.as_str() {
+        "ir" => serde_json::to_string_pretty(&k)?,
+        x => return Err(format!("unknown emit format `{}`", x).into()),
+    };
+    write_output(&opt.output, &text, log)
+}
+
+fn run_diff(opt: DiffOpt, log: &Logger) -> Result<()> {
+    let old = read_model(&opt.old, log)?;
+    let new = read_model(&opt.new, log)?;
+    let changes = old.diff(&new, lint::Path::default());
+    // Changes are the result, not diagnostics.
+    for x in changes.iter() {
+        println!("- {}", x);
+    }
+    match changes.iter().filter(|x| x.breakage.is_breaking()).count() {
+        0 => Ok(()),
+        n => Err(Failure::Rejected(format!("{} breaking change(s) found", n))),
+    }
+}
+
+fn run_fmt(opt: FmtOpt, log: &Logger) -> Result<()> {
+    let src = read_file(&opt.input)?;
+    let text = serde_yaml::to_string(&serde_yaml::from_str::<serde_yaml::Value>(&src)?)?;
+    match opt.check {
+        true if text == src => Ok(()),
+        true => Err(Failure::Rejected(format!("`{}` is not formatted", opt.input))),
+        false => write_output(&opt.output, &text, log),
+    }
+}
+
+/// Reads an OpenAPI 3.0 schema file into KCG model.
+/// Fails if the schema doesn't pass lint.
+fn read_model(path: &str, log: &Logger) -> Result<model::Doc1> {
+    let src = read_file(path)?;
     let oas = serde_yaml::from_str::<scan::openapi3::model::Doc>(&src)?;
 
     // Lint.
     let mut x = lint::Context::default();
     oas.lint(lint::Path::default(), &mut x);
-    println!("{}", x);
-    x.check()?;
+    log.lints(x)?;
+
+    // Scan.
+    oas.scan(lint::Path::default()).map_err(Failure::Rejected)
+}
 
-    match opt.output {
-        None => (),
+/// Writes `text` to file at `path`, or to standard output if `path` is `None`.
+fn write_output(path: &Option<String>, text: &str, log: &Logger) -> Result<()> {
+    match path {
+        None => print!("{}", text),
         Some(x) => {
-            // Scan.
-            let k = oas.scan(lint::Path::default())?;
-
-            // Code-gen.
-            let mut code = String::new();
-            code.push_str(&read_file_or_default(opt.prelude)?);
-            code.push_str("\n\n");
-            code.push_str(&k.code(&opt.skippings));
-            std::fs::write(&x, code)?;
+            std::fs::write(x, text).map_err(|e| format!("cannot write `{}`: {}", x, e))?;
+            log.progress(&format!("wrote `{}`", x));
         },
     }
     Ok(())
 }
 
-fn read_file_or_default(path:Option<String>) -> Result<String,Box<dyn std::error::Error>> {
-    match path {
-        None => Ok(String::default()),
-        Some(x) => Ok(std::fs::read_to_string(&x)?),
+/// Prints diagnostics to standard error.
+/// Standard output is reserved for results, so they can be piped.
+struct Logger {
+    /// Minimum severity to print.
+    level: lint::Severity,
+    /// Configuration with lint levels.
+    config: config::Config,
+}
+impl Logger {
+    /// Prints lint logs with configured lint levels, and fails if there's any error.
+    fn lints(&self, mut context: lint::Context) -> Result<()> {
+        self.config.adjust_lints(&mut context);
+        for x in context.logs.iter() {
+            self.print(x);
+        }
+        context.check().map_err(|_| {
+            let n = context.logs.iter().filter(|x| x.severity == lint::Severity::Error).count();
+            Failure::Rejected(format!("{} lint error(s) found", n))
+        })
     }
-}
\ No newline at end of file
+    /// Prints a lint log adjusted by lint levels if it's severe enough.
+    fn print(&self, log: &lint::Log) {
+        if log.severity >= self.level {
+            eprintln!("- {}", log);
+        }
+    }
+    /// Prints a progress message in verbose mode.
+    fn progress(&self, message: &str) {
+        if self.level == lint::Severity::Info {
+            eprintln!("{}", message);
+        }
+    }
+}
+
+fn parse_format_type(s: &str) -> std::result::Result<(String,String),String> {
+    match s.split_once('=') {
+        Some((format, r#type)) if !format.is_empty() && !r#type.is_empty() => Ok((format.to_string(), r#type.to_string())),
+        _ => Err(format!("expected `<format>=<type>` but got `{}`", s)),
+    }
+}
+
+fn read_file(path: &str) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|e| Failure::from(format!("cannot read `{}`: {}", path, e)))
+}
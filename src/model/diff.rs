@@ -0,0 +1,213 @@
+//! Compatibility analysis between two versions of a model.
+//! Changes are classified by serialized forms, so changes only in generated code (e.g. docs) are not reported.
+
+use crate::lint::Path;
+use super::Doc1;
+use super::message::*;
+
+/// Parties a change breaks.
+/// - Readers: Old readers may fail to read values written by new writers.
+/// - Writers: New readers may fail to read values written by old writers.
+#[derive(Clone,Copy)]
+#[derive(Eq,PartialEq)]
+#[derive(Debug)]
+pub enum KBreakage {
+    Compatible,
+    Readers,
+    Writers,
+    Both,
+}
+impl KBreakage {
+    pub fn is_breaking(&self) -> bool {
+        *self != KBreakage::Compatible
+    }
+}
+impl std::fmt::Display for KBreakage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use KBreakage::*;
+        f.write_str(match self {
+            Compatible => "compatible",
+            Readers => "breaks readers",
+            Writers => "breaks writers",
+            Both => "breaks readers and writers",
+        })
+    }
+}
+
+/// A difference between two versions of a model.
+#[derive(Clone)]
+#[derive(Eq,PartialEq)]
+#[derive(Debug)]
+pub struct KChange {
+    pub breakage: KBreakage,
+    pub path: Path,
+    pub message: &'static str,
+}
+impl std::fmt::Display for KChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}: {}", self.breakage, self.path, self.message)
+    }
+}
+
+impl Doc1 {
+    /// Lists changes from `self` to `new`.
+    /// Types, fields and cases are matched by name, and variants are matched by tag.
+    pub fn diff(&self, new: &Doc1, path: Path) -> Vec<KChange> {
+        let path = path.appending("types");
+        let mut z = Changes::default();
+        for a in self.types.iter() {
+            match new.types.iter().find(|b| b.name() == a.name()) {
+                None => z.push(KBreakage::Both, path.appending(a.name()), "type removed"),
+                Some(b) => a.diff(b, path.appending(a.name()), &mut z),
+            }
+        }
+        for b in new.types.iter().filter(|b| !self.types.iter().any(|a| a.name() == b.name())) {
+            z.push(KBreakage::Compatible, path.appending(b.name()), "type added");
+        }
+        z.0
+    }
+}
+
+#[derive(Default)]
+struct Changes(Vec<KChange>);
+impl Changes {
+    fn push(&mut self, breakage: KBreakage, path: Path, message: &'static str) {
+        self.0.push(KChange { breakage, path, message })
+    }
+}
+
+impl KType {
+    fn diff(&self, new: &KType, path: Path, z: &mut Changes) {
+        use KType::*;
+        match (self, new) {
+            (New(a), New(b)) => {
+                if a.origin != b.origin { z.push(KBreakage::Both, path.appending("origin"), "origin type changed") }
+                a.constraints.diff(&b.constraints, path, z);
+            },
+            (Enum(a), Enum(b)) => a.diff(b, path, z),
+            (Sum(a), Sum(b)) => a.diff(b, path, z),
+            (Prod(a), Prod(b)) => a.diff(b, path, z),
+            _ => z.push(KBreakage::Both, path, "kind of type changed"),
+        }
+    }
+}
+
+impl KEnumType {
+    fn diff(&self, new: &KEnumType, path: Path, z: &mut Changes) {
+        for a in self.cases.iter().filter(|a| !new.cases.iter().any(|b| b.name == a.name)) {
+            z.push(KBreakage::Writers, path.appending(&a.name), "case removed");
+        }
+        for b in new.cases.iter().filter(|b| !self.cases.iter().any(|a| a.name == b.name)) {
+            z.push(KBreakage::Readers, path.appending(&b.name), "case added");
+        }
+    }
+}
+
+impl KSumType {
+    fn diff(&self, new: &KSumType, path: Path, z: &mut Changes) {
+        if self.tagging != new.tagging { z.push(KBreakage::Both, path.clone(), "tagging changed") }
+        if self.discriminant != new.discriminant { z.push(KBreakage::Both, path.clone(), "discriminant renamed") }
+        // Variants are serialized by tag, so renaming variants keeps serialized form.
+        for a in self.variants.iter() {
+            let subpath = path.appending(&a.name);
+            match new.variants.iter().find(|b| b.tag == a.tag) {
+                None => z.push(KBreakage::Writers, subpath, "variant removed"),
+                Some(b) => a.content.diff(&b.content, subpath, z),
+            }
+        }
+        for b in new.variants.iter().filter(|b| !self.variants.iter().any(|a| a.tag == b.tag)) {
+            z.push(KBreakage::Readers, path.appending(&b.name), "variant added");
+        }
+    }
+}
+
+impl KProdType {
+    fn diff(&self, new: &KProdType, path: Path, z: &mut Changes) {
+        for a in self.fields.iter() {
+            let subpath = path.appending(&a.name);
+            match new.fields.iter().find(|b| b.name == a.name) {
+                // Unknown fields are ignored on read.
+                None if a.content.optional || a.default.is_some() => z.push(KBreakage::Compatible, subpath, "optional field removed"),
+                None => z.push(KBreakage::Readers, subpath, "required field removed"),
+                Some(b) => {
+                    match (a.is_required(), b.is_required()) {
+                        (false, true) => z.push(KBreakage::Writers, subpath.clone(), "field made required"),
+                        (true, false) => z.push(KBreakage::Readers, subpath.clone(), "field made optional"),
+                        _ => (),
+                    }
+                    a.content.diff(&b.content, subpath.clone(), z);
+                    a.constraints.diff(&b.constraints, subpath, z);
+                },
+            }
+        }
+        for b in new.fields.iter().filter(|b| !self.fields.iter().any(|a| a.name == b.name)) {
+            match b.is_required() {
+                true => z.push(KBreakage::Writers, path.appending(&b.name), "required field added"),
+                false => z.push(KBreakage::Compatible, path.appending(&b.name), "optional field added"),
+            }
+        }
+    }
+}
+
+impl KProdTypeField {
+    /// Whether value of this field must be present in serialized form.
+    fn is_required(&self) -> bool {
+        !self.content.optional && self.default.is_none()
+    }
+}
+
+impl KContentStorage {
+    /// Compares stored content. Optionality is compared by containers.
+    fn diff(&self, new: &KContentStorage, path: Path, z: &mut Changes) {
+        if self.array != new.array || self.r#type != new.r#type {
+            z.push(KBreakage::Both, path.clone(), "content type changed");
+        }
+        match (self.nullable, new.nullable) {
+            (false, true) => z.push(KBreakage::Readers, path, "content made nullable"),
+            (true, false) => z.push(KBreakage::Writers, path, "content made non-nullable"),
+            _ => (),
+        }
+    }
+}
+
+impl KConstraints {
+    /// Tightened constraints reject values old writers could write.
+    /// Loosened constraints accept more values, and they are treated as compatible.
+    fn diff(&self, new: &KConstraints, path: Path, z: &mut Changes) {
+        let number = |x: &Option<serde_json::Number>| x.as_ref().and_then(|x| x.as_f64());
+        let changes = [
+            ("minimum", bound_tightened(number(&self.minimum), number(&new.minimum), true)),
+            ("maximum", bound_tightened(number(&self.maximum), number(&new.maximum), false)),
+            ("minLength", bound_tightened(self.min_length, new.min_length, true)),
+            ("maxLength", bound_tightened(self.max_length, new.max_length, false)),
+            ("pattern", match (&self.pattern, &new.pattern) {
+                (None, None) => None,
+                (Some(_), None) => Some(false),
+                // Patterns cannot be compared, so any other change can reject old values.
+                (a, b) => if a == b { None } else { Some(true) },
+            }),
+            ("minItems", bound_tightened(self.min_items, new.min_items, true)),
+            ("maxItems", bound_tightened(self.max_items, new.max_items, false)),
+            ("uniqueItems", bound_tightened(Some(self.unique_items), Some(new.unique_items), true)),
+        ];
+        for (name,x) in changes {
+            match x {
+                Some(true) => z.push(KBreakage::Writers, path.appending(name), "constraint tightened"),
+                Some(false) => z.push(KBreakage::Compatible, path.appending(name), "constraint loosened"),
+                None => (),
+            }
+        }
+    }
+}
+
+/// `Some(true)` if bound is tightened, `Some(false)` if loosened, and `None` if unchanged.
+/// - `lower`: Whether values must be greater than or equal to the bound.
+fn bound_tightened<T: PartialOrd>(old: Option<T>, new: Option<T>, lower: bool) -> Option<bool> {
+    match (old, new) {
+        (None, None) => None,
+        (None, Some(_)) => Some(true),
+        (Some(_), None) => Some(false),
+        (Some(a), Some(b)) if a == b => None,
+        (Some(a), Some(b)) => Some((b > a) == lower),
+    }
+}
@@ -0,0 +1,49 @@
+#![cfg(test)]
+//! Model fixtures shared by tests.
+//! Helpers make minimal values, and tests set only properties they care about.
+
+use extend::ext;
+use super::message::*;
+
+pub fn def(name: &str) -> KTypeRef {
+    KTypeRef::Def(name.to_string())
+}
+pub fn prim(x: KPrimType) -> KTypeRef {
+    KTypeRef::Prim(x)
+}
+/// Required, non-null and single-valued field.
+pub fn field(name: &str, r#type: KTypeRef) -> KProdTypeField {
+    KProdTypeField {
+        name: name.to_string(),
+        content: KContentStorage { r#type, ..Default::default() },
+        ..Default::default()
+    }
+}
+/// Variant tagged with its name.
+pub fn variant(name: &str, r#type: KTypeRef) -> KSumTypeVariant {
+    KSumTypeVariant {
+        name: name.to_string(),
+        tag: name.to_string(),
+        content: KContentStorage { r#type, ..Default::default() },
+        ..Default::default()
+    }
+}
+pub fn prod_type(name: &str, fields: Vec<KProdTypeField>) -> KType {
+    KType::Prod(KProdType { name: name.to_string(), fields, ..Default::default() })
+}
+
+#[ext(name=KProdTypeFieldFixture)]
+pub impl KProdTypeField {
+    fn optional(mut self) -> Self {
+        self.content.optional = true;
+        self
+    }
+    fn nullable(mut self) -> Self {
+        self.content.nullable = true;
+        self
+    }
+    fn array(mut self) -> Self {
+        self.content.array = true;
+        self
+    }
+}
@@ -0,0 +1,194 @@
+//! Type reference graph analysis.
+
+use itertools::Itertools;
+use super::Doc1;
+use super::message::*;
+
+/// A reference from a member of a type to another type.
+#[derive(Clone,Copy)]
+#[derive(Eq,PartialEq)]
+#[derive(Debug)]
+pub struct KTypeEdge<'a> {
+    pub from: &'a str,
+    /// Name of referencing field/variant.
+    pub member: &'a str,
+    pub to: &'a str,
+    /// Referenced values are stored out of the referencing value. (e.g. array)
+    /// Indirect references do not make infinitely sized types.
+    pub indirect: bool,
+}
+
+impl Doc1 {
+    /// Lists references between defined types.
+    pub fn type_edges(&self) -> Vec<KTypeEdge<'_>> {
+        let mut z = Vec::new();
+        for x in self.types.iter() {
+            let contents = match x {
+                KType::New(x) => {
+                    if let KTypeRef::Def(to) = &x.origin {
+                        z.push(KTypeEdge { from: &x.name, member: "origin", to, indirect: false });
+                    }
+                    continue
+                },
+                KType::Enum(_) => continue,
+                KType::Sum(x) => x.variants.iter().map(|x| (x.name.as_str(), &x.content)).collect::<Vec<_>>(),
+                KType::Prod(x) => x.fields.iter().map(|x| (x.name.as_str(), &x.content)).collect::<Vec<_>>(),
+            };
+            for (member,content) in contents {
+                if let KTypeRef::Def(to) = &content.r#type {
+                    z.push(KTypeEdge { from: x.name(), member, to, indirect: content.array });
+                }
+            }
+        }
+        z
+    }
+    /// Finds strongly connected components of type reference graph.
+    /// Components are ordered in reverse topological order. (Tarjan's algorithm)
+    pub fn type_sccs(&self) -> Vec<Vec<&str>> {
+        let names = self.types.iter().map(|x| x.name()).collect::<Vec<_>>();
+        let edges = self.type_edges();
+        let mut tarjan = Tarjan {
+            names: &names,
+            edges: &edges,
+            index: vec![None; names.len()],
+            lowlink: vec![0; names.len()],
+            stack: Vec::new(),
+            on_stack: vec![false; names.len()],
+            counter: 0,
+            sccs: Vec::new(),
+        };
+        for i in 0..names.len() {
+            if tarjan.index[i].is_none() { tarjan.visit(i) }
+        }
+        tarjan.sccs
+    }
+    /// Finds types containing primitive types matching `predicate` directly or transitively.
+    pub fn types_containing(&self, predicate: impl Fn(&KPrimType) -> bool) -> Vec<&str> {
+        let mut z = Vec::<&str>::new();
+        for x in self.types.iter() {
+            let found = x.type_refs().iter().any(|(_,x)| match x { KTypeRef::Prim(x) => predicate(x), _ => false });
+            if found { z.push(x.name()) }
+        }
+        let edges = self.type_edges();
+        loop {
+            let found = edges.iter().filter(|x| z.contains(&x.to) && !z.contains(&x.from)).map(|x| x.from).collect::<Vec<_>>();
+            if found.is_empty() { return z }
+            for x in found {
+                if !z.contains(&x) { z.push(x) }
+            }
+        }
+    }
+    /// Finds minimal set of references to be stored indirectly to make all types finitely sized.
+    /// Already indirect references are not considered.
+    /// Returns edges in definition order of referencing types.
+    pub fn recursive_type_edges(&self) -> Vec<KTypeEdge<'_>> {
+        let edges = self.type_edges();
+        let mut z = Vec::new();
+        for scc in self.type_sccs() {
+            let direct_edges = edges.iter()
+                .filter(|x| !x.indirect && scc.contains(&x.from) && scc.contains(&x.to))
+                .copied()
+                .collect::<Vec<_>>();
+            z.extend(find_cuts(&scc, &direct_edges));
+        }
+        let order = |x: &KTypeEdge| edges.iter().position(|e| e == x);
+        z.sort_by_key(order);
+        z
+    }
+}
+
+struct Tarjan<'a,'b> {
+    names: &'b [&'a str],
+    edges: &'b [KTypeEdge<'a>],
+    index: Vec<Option<usize>>,
+    lowlink: Vec<usize>,
+    stack: Vec<usize>,
+    on_stack: Vec<bool>,
+    counter: usize,
+    sccs: Vec<Vec<&'a str>>,
+}
+impl<'a,'b> Tarjan<'a,'b> {
+    fn visit(&mut self, i: usize) {
+        self.index[i] = Some(self.counter);
+        self.lowlink[i] = self.counter;
+        self.counter += 1;
+        self.stack.push(i);
+        self.on_stack[i] = true;
+        let targets = self.edges.iter()
+            .filter(|x| x.from == self.names[i])
+            .filter_map(|x| self.names.iter().position(|name| *name == x.to))
+            .collect::<Vec<_>>();
+        for j in targets {
+            match self.index[j] {
+                None => {
+                    self.visit(j);
+                    self.lowlink[i] = self.lowlink[i].min(self.lowlink[j]);
+                },
+                Some(x) if self.on_stack[j] => self.lowlink[i] = self.lowlink[i].min(x),
+                Some(_) => (),
+            }
+        }
+        if Some(self.lowlink[i]) == self.index[i] {
+            let mut scc = Vec::new();
+            while let Some(j) = self.stack.pop() {
+                self.on_stack[j] = false;
+                scc.push(self.names[j]);
+                if j == i { break }
+            }
+            scc.reverse();
+            self.sccs.push(scc);
+        }
+    }
+}
+
+/// Finds a minimum set of edges to break all cycles.
+/// Finding minimum set is NP-hard, so large components get a minimal set instead.
+fn find_cuts<'a>(nodes: &[&'a str], edges: &[KTypeEdge<'a>]) -> Vec<KTypeEdge<'a>> {
+    // DFS back edges break all cycles.
+    let mut cuts = Vec::new();
+    let mut visited = Vec::new();
+    for x in nodes.iter() {
+        find_back_edges(x, edges, &mut visited, &mut Vec::new(), &mut cuts);
+    }
+    // Search smaller sets exhaustively.
+    const MAX_EXHAUSTIVE_SEARCH_EDGES: usize = 16;
+    if edges.len() <= MAX_EXHAUSTIVE_SEARCH_EDGES {
+        for n in 0..cuts.len() {
+            let found = edges.iter().copied().combinations(n).find(|xs| is_acyclic(nodes, edges.iter().filter(|e| !xs.contains(e))));
+            if let Some(xs) = found { return xs }
+        }
+        return cuts;
+    }
+    // Drop redundant ones.
+    let mut i = 0;
+    while i < cuts.len() {
+        let x = cuts.remove(i);
+        if is_acyclic(nodes, edges.iter().filter(|e| !cuts.contains(e))) { continue }
+        cuts.insert(i, x);
+        i += 1;
+    }
+    cuts
+}
+
+fn find_back_edges<'a>(node: &'a str, edges: &[KTypeEdge<'a>], visited: &mut Vec<&'a str>, path: &mut Vec<&'a str>, cuts: &mut Vec<KTypeEdge<'a>>) {
+    if visited.contains(&node) { return }
+    visited.push(node);
+    path.push(node);
+    for x in edges.iter().filter(|x| x.from == node) {
+        if path.contains(&x.to) { cuts.push(*x) }
+        else { find_back_edges(x.to, edges, visited, path, cuts) }
+    }
+    path.pop();
+}
+
+fn is_acyclic<'a,'b>(nodes: &[&'a str], edges: impl Iterator<Item=&'b KTypeEdge<'a>>) -> bool where 'a: 'b {
+    let edges = edges.collect::<Vec<_>>();
+    let mut remaining = nodes.to_vec();
+    loop {
+        let source = remaining.iter().position(|x| !edges.iter().any(|e| e.to == *x && remaining.contains(&e.from)));
+        match source {
+            None => return remaining.is_empty(),
+            Some(i) => { remaining.remove(i); },
+        }
+    }
+}
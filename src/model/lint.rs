@@ -0,0 +1,125 @@
+//! Semantic lint over KCG model.
+//! OpenAPI lint checks schema patterns, and this checks whether scanned types can live together in generated code.
+
+use crate::lint::*;
+use super::Doc1;
+use super::message::*;
+
+impl Lint for Doc1 {
+    fn lint(&self, path: Path, context: &mut Context) {
+        self.lint_type_names(path.appending("types"), context);
+        self.lint_type_refs(path.appending("types"), context);
+        self.lint_type_attrs(path.appending("types"), context);
+    }
+}
+
+impl Doc1 {
+    /// Reports types that code-gen skips but other generated types reference.
+    /// Definitions of these types must be provided by prelude code.
+    /// - `skipping_type_names`: Type names to be skipped in addition to `KTypeAttrs::skip`.
+    pub fn lint_skipped_types(&self, skipping_type_names: &[String], path: Path, context: &mut Context) {
+        let path = path.appending("types");
+        for name in skipping_type_names {
+            if !self.types.iter().any(|x| x.name() == name) {
+                context.warning(path.appending(name), SKIPPED_TYPE_UNDEFINED);
+            }
+        }
+        let is_skipped = |name: &str| self.types.iter().any(|x| x.name() == name && (x.attrs().skip || skipping_type_names.iter().any(|x| x == name)));
+        for x in self.types.iter().filter(|x| !is_skipped(x.name())) {
+            for (member,r) in x.type_refs() {
+                if let KTypeRef::Def(name) = r {
+                    if is_skipped(name) {
+                        context.info(path.appending(x.name()).appending(member), SKIPPED_TYPE_REFERENCED);
+                    }
+                }
+            }
+        }
+    }
+    fn lint_type_refs(&self, path: Path, context: &mut Context) {
+        let mut referenced_names = Vec::<&str>::new();
+        for x in self.types.iter() {
+            for (member,r) in x.type_refs() {
+                if let KTypeRef::Def(name) = r {
+                    if !self.types.iter().any(|x| x.name() == name) {
+                        context.error(path.appending(x.name()).appending(member), "references undefined type");
+                    }
+                    if name != x.name() { referenced_names.push(name) }
+                }
+            }
+        }
+        for x in self.types.iter() {
+            if !referenced_names.contains(&x.name()) {
+                context.warning(path.appending(x.name()), TYPE_UNREFERENCED);
+            }
+        }
+    }
+    fn lint_type_names(&self, path: Path, context: &mut Context) {
+        let mut names = Vec::<String>::new();
+        for x in self.types.iter() {
+            let name = x.name();
+            if syn::parse_str::<syn::Ident>(name).is_err() {
+                context.error(path.appending(name), "type name is not a valid Rust identifier");
+            }
+            if is_reserved_type_name(name) {
+                context.error(path.appending(name), "type name collides with a Rust prelude/std name");
+            }
+            // Case-insensitive file systems (macOS, Windows) cannot keep files for these types separately.
+            if names.contains(&name.to_lowercase()) {
+                context.error(path.appending(name), "type name collides with another type name case-insensitively");
+            }
+            names.push(name.to_lowercase());
+        }
+    }
+    /// Checks Rust code designated by type attributes.
+    /// Scanned attributes are checked by OpenAPI lint, but IR files can be written by hand.
+    fn lint_type_attrs(&self, path: Path, context: &mut Context) {
+        for x in self.types.iter() {
+            let path = path.appending(x.name());
+            if let Some(r#type) = x.attrs().rust_type.as_ref() {
+                if syn::parse_str::<syn::Type>(r#type).is_err() {
+                    context.error(path.appending("rust_type"), "must be a valid Rust type expression");
+                }
+            }
+            for r#trait in x.attrs().derive.iter() {
+                if syn::parse_str::<syn::Path>(r#trait).is_err() {
+                    context.error(path.appending("derive").appending(r#trait), "must be a valid trait path");
+                }
+            }
+        }
+    }
+    /// Renames types with reserved names or case-insensitively duplicated names.
+    /// Conflicting names get `prefix` and `suffix` until they do not conflict.
+    /// First one of duplicated names keeps its name.
+    pub fn rename_conflicting_types(&mut self, prefix: &str, suffix: &str) {
+        if prefix.is_empty() && suffix.is_empty() { return }
+        let mut names = Vec::<String>::new();
+        let mut renames = Vec::<(String,String)>::new();
+        let all_names = self.types.iter().map(|x| x.name().to_lowercase()).collect::<Vec<_>>();
+        for x in self.types.iter() {
+            let mut name = x.name().to_string();
+            while is_reserved_type_name(&name) || names.contains(&name.to_lowercase()) || (name != x.name() && all_names.contains(&name.to_lowercase())) {
+                name = format!("{}{}{}", prefix, name, suffix);
+            }
+            if name != x.name() { renames.push((x.name().to_string(), name.clone())) }
+            names.push(name.to_lowercase());
+        }
+        for (from,to) in renames.iter() {
+            self.rename_type(from, to);
+        }
+    }
+}
+
+/// Rust prelude/std names likely to be used by generated code.
+const RESERVED_TYPE_NAMES: &[&str] = &[
+    "bool", "char", "str", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32", "f64",
+    "Self", "String", "Option", "Some", "None", "Result", "Ok", "Err", "Vec", "Box", "Rc", "Arc", "Cell", "RefCell",
+    "HashMap", "HashSet", "BTreeMap", "BTreeSet",
+    "Copy", "Clone", "Send", "Sync", "Sized", "Unpin", "Drop", "Fn", "FnMut", "FnOnce",
+    "Default", "Debug", "Display", "Eq", "PartialEq", "Ord", "PartialOrd", "Hash",
+    "From", "Into", "TryFrom", "TryInto", "AsRef", "AsMut", "ToOwned", "ToString", "FromStr",
+    "Iterator", "IntoIterator", "Extend", "Serialize", "Deserialize",
+];
+
+fn is_reserved_type_name(name: &str) -> bool {
+    RESERVED_TYPE_NAMES.contains(&name)
+}
@@ -8,6 +8,7 @@
 use serde_derive::{Serialize, Deserialize};
 
 #[derive(Serialize,Deserialize)]
+#[derive(Clone)]
 #[derive(Eq,PartialEq)]
 #[derive(Debug)]
 pub enum KType {
@@ -26,21 +27,64 @@ impl KType {
             Prod(x) => x.name.as_str(),
         }
     }
+    pub fn attrs(&self) -> &KTypeAttrs {
+        use KType::*;
+        match self {
+            New(x) => &x.attrs,
+            Enum(x) => &x.attrs,
+            Sum(x) => &x.attrs,
+            Prod(x) => &x.attrs,
+        }
+    }
+    /// References to other types with names of referencing members.
+    /// New-type's reference is named `origin`.
+    pub fn type_refs(&self) -> Vec<(&str,&KTypeRef)> {
+        use KType::*;
+        match self {
+            New(x) => vec![("origin", &x.origin)],
+            Enum(_) => vec![],
+            Sum(x) => x.variants.iter().map(|x| (x.name.as_str(), &x.content.r#type)).collect(),
+            Prod(x) => x.fields.iter().map(|x| (x.name.as_str(), &x.content.r#type)).collect(),
+        }
+    }
 }
 
+/// Code-gen controls attached to a type.
+/// These are not part of data structure, but affect how code-gen treats the type.
 #[derive(Serialize,Deserialize)]
+#[derive(Clone)]
+#[derive(Eq,PartialEq)]
+#[derive(Default)]
+#[derive(Debug)]
+pub struct KTypeAttrs {
+    /// Skips code-gen for this type.
+    /// You are supposed to provide type definition yourself.
+    pub skip: bool,
+    /// Name of a Rust type to alias instead of generating type definition.
+    pub rust_type: Option<String>,
+    /// Additional traits to derive on generated type.
+    pub derive: Vec<String>,
+    /// Deprecation note. `Some` if deprecated, and the note can be empty.
+    pub deprecated: Option<String>,
+}
+
+#[derive(Serialize,Deserialize)]
+#[derive(Clone)]
 #[derive(Eq,PartialEq)]
 #[derive(Default)]
 #[derive(Debug)]
 pub struct KNewType {
     pub name: String,
     pub origin: KTypeRef,
+    pub constraints: KConstraints,
     pub comment: String,
+    pub attrs: KTypeAttrs,
 }
 
 /// Finite constant value set.
 /// Rust code-gen provides automatic enum/string conversion.
 #[derive(Serialize,Deserialize)]
+#[derive(Clone)]
 #[derive(Eq,PartialEq)]
 #[derive(Default)]
 #[derive(Debug)]
@@ -48,19 +92,28 @@ pub struct KEnumType {
     pub name: String,
     pub cases: Vec<KEnumTypeCase>,
     pub comment: String,
+    pub attrs: KTypeAttrs,
 }
 #[derive(Serialize,Deserialize)]
+#[derive(Clone)]
 #[derive(Eq,PartialEq)]
 #[derive(Default)]
 #[derive(Debug)]
 pub struct KEnumTypeCase {
+    /// Serialized name of this case.
     pub name: String,
+    /// Name of this case in generated code.
+    /// Made from `name` if this is `None`.
+    pub var_name: Option<String>,
     // /// Underlying value for this case.
     // pub value: Option<i32>,
     pub comment: String,
+    /// Deprecation note. `Some` if deprecated, and the note can be empty.
+    pub deprecated: Option<String>,
 }
 
 #[derive(Serialize,Deserialize)]
+#[derive(Clone)]
 #[derive(Eq,PartialEq)]
 #[derive(Default)]
 #[derive(Debug)]
@@ -68,24 +121,51 @@ pub struct KSumType {
     pub name: String,
     /// Discriminant field name. 
     /// This controls how variant discriminants to be serialized.
+    /// Ignored for externally tagged and untagged sum-types.
     pub discriminant: String,
+    pub tagging: KSumTypeTagging,
     pub variants: Vec<KSumTypeVariant>,
     pub comment: String,
+    pub attrs: KTypeAttrs,
+}
+/// Serialized form of sum-type variants.
+#[derive(Serialize,Deserialize)]
+#[derive(Clone)]
+#[derive(Eq,PartialEq)]
+#[derive(Default)]
+#[derive(Debug)]
+pub enum KSumTypeTagging {
+    /// Discriminant is stored in variant content object. `{"type": "Cat", ...}`
+    #[default]
+    Internal,
+    /// Variant content is wrapped in an object with single discriminant key. `{"Cat": {...}}`
+    External,
+    /// Discriminant and variant content are stored side by side. `{"type": "Cat", "value": {...}}`
+    Adjacent { content: String },
+    /// No discriminant. Variants are distinguished only by their content.
+    Untagged,
 }
 #[derive(Serialize,Deserialize)]
+#[derive(Clone)]
 #[derive(Eq,PartialEq)]
 #[derive(Default)]
 #[derive(Debug)]
 pub struct KSumTypeVariant {
     pub name: String,
+    /// Discriminant value serialized for this variant.
+    /// This is same with `name` unless remapped by schema.
+    pub tag: String,
     /// Type of stored data in this sum-type variant.
     /// Name-based sum-types can define array/optional content.
     /// Type-based sum-types only can define explicit reference to other type.
     pub content: KContentStorage,
     pub comment: String,
+    /// Deprecation note. `Some` if deprecated, and the note can be empty.
+    pub deprecated: Option<String>,
 }
 
 #[derive(Serialize,Deserialize)]
+#[derive(Clone)]
 #[derive(Eq,PartialEq)]
 #[derive(Default)]
 #[derive(Debug)]
@@ -93,31 +173,63 @@ pub struct KProdType {
     pub name: String,
     pub fields: Vec<KProdTypeField>,
     pub comment: String,
+    pub attrs: KTypeAttrs,
 }
 #[derive(Serialize,Deserialize)]
+#[derive(Clone)]
 #[derive(Eq,PartialEq)]
 #[derive(Default)]
 #[derive(Debug)]
 pub struct KProdTypeField {
     pub name: String,
     pub content: KContentStorage,
+    pub constraints: KConstraints,
+    /// Value used if the field is missing in serialized form.
+    pub default: Option<serde_json::Value>,
     pub comment: String,
+    /// Deprecation note. `Some` if deprecated, and the note can be empty.
+    pub deprecated: Option<String>,
 }
 
 /// An inveted concept to simplify type definition.
 /// Proper support for optional/array types will require full support for generics.
 /// To eliminate complexity of generics support, I just baked-in some essential generic patterns.
 #[derive(Serialize,Deserialize)]
+#[derive(Clone)]
 #[derive(Eq,PartialEq)]
 #[derive(Default)]
 #[derive(Debug)]
 pub struct KContentStorage {
+    /// Content may be absent.
     pub optional: bool,
+    /// Content may be `null`.
+    /// Optional and nullable content distinguishes absent and `null`.
+    pub nullable: bool,
     pub array: bool,
     pub r#type: KTypeRef,
 }
 
+/// Value constraints checked at runtime.
+/// Item constraints (`min_items`, `max_items`, `unique_items`) apply to arrays,
+/// and others apply to each element if content is an array.
+#[derive(Serialize,Deserialize)]
+#[derive(Clone)]
+#[derive(Eq,PartialEq)]
+#[derive(Default)]
+#[derive(Debug)]
+pub struct KConstraints {
+    pub minimum: Option<serde_json::Number>,
+    pub maximum: Option<serde_json::Number>,
+    pub min_length: Option<u64>,
+    pub max_length: Option<u64>,
+    pub pattern: Option<String>,
+    pub min_items: Option<u64>,
+    pub max_items: Option<u64>,
+    pub unique_items: bool,
+}
+
 #[derive(Serialize,Deserialize)]
+#[derive(Clone)]
 #[derive(Eq,PartialEq)]
 #[derive(Debug)]
 pub enum KTypeRef {
@@ -139,6 +251,7 @@ impl Default for KTypeRef {
 
 /// A simple value with no substructure.
 #[derive(Serialize,Deserialize)]
+#[derive(Clone)]
 #[derive(Eq,PartialEq)]
 #[derive(Debug)]
 pub enum KPrimType {
@@ -148,4 +261,16 @@ pub enum KPrimType {
     F32,
     F64,
     String,
+    /// RFC 3339 date-time string. (`format: date-time`)
+    DateTime,
+    /// RFC 3339 full-date string. (`format: date`)
+    Date,
+    /// UUID string. (`format: uuid`)
+    Uuid,
+    /// URI string. (`format: uri`)
+    Uri,
+    /// Base64 encoded bytes. (`format: byte`)
+    Byte,
+    /// Raw bytes. (`format: binary`)
+    Binary,
 }
\ No newline at end of file
@@ -1,5 +1,11 @@
 pub mod feature;
 pub mod message;
+pub mod graph;
+pub mod diff;
+mod rename;
+mod lint;
+pub(crate) mod fixture;
+mod test;
 
 use serde_derive::{Serialize, Deserialize};
 use feature::HService;
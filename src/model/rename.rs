@@ -0,0 +1,61 @@
+use extend::ext;
+use super::Doc1;
+use super::message::*;
+
+impl Doc1 {
+    /// Renames a defined type and all references to it.
+    /// Serialized forms are kept as is.
+    /// Sum-type variants named after the renamed type are renamed together, but keep their tags.
+    pub fn rename_type(&mut self, from: &str, to: &str) {
+        for x in self.types.iter_mut() {
+            x.rename_type(from, to);
+        }
+        for x in self.funcs.iter_mut().flat_map(|x| x.funcs.iter_mut()) {
+            x.input.body.rename_type(from, to);
+            for x in x.output.cases.iter_mut() {
+                x.body.rename_type(from, to);
+            }
+        }
+    }
+}
+
+impl KType {
+    fn rename_type(&mut self, from: &str, to: &str) {
+        use KType::*;
+        match self {
+            New(x) => {
+                x.name.rename_type(from, to);
+                x.origin.rename_type(from, to);
+            },
+            Enum(x) => {
+                x.name.rename_type(from, to);
+            },
+            Sum(x) => {
+                x.name.rename_type(from, to);
+                for x in x.variants.iter_mut() {
+                    if x.content.r#type == KTypeRef::Def(from.to_string()) { x.name.rename_type(from, to) }
+                    x.content.r#type.rename_type(from, to);
+                }
+            },
+            Prod(x) => {
+                x.name.rename_type(from, to);
+                for x in x.fields.iter_mut() {
+                    x.content.r#type.rename_type(from, to);
+                }
+            },
+        }
+    }
+}
+
+impl KTypeRef {
+    fn rename_type(&mut self, from: &str, to: &str) {
+        if let KTypeRef::Def(x) = self { x.rename_type(from, to) }
+    }
+}
+
+#[ext(name=StringRenameUtil)]
+impl String {
+    fn rename_type(&mut self, from: &str, to: &str) {
+        if self == from { *self = to.to_string() }
+    }
+}
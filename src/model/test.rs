@@ -0,0 +1,251 @@
+#![cfg(test)]
+
+use crate::lint::*;
+use super::Doc1;
+use super::message::*;
+use super::fixture::*;
+
+fn new_type(name: &str) -> KType {
+    KType::New(KNewType {
+        name: name.to_string(),
+        origin: KTypeRef::Prim(KPrimType::String),
+        constraints: KConstraints::default(),
+        comment: "".to_string(),
+        attrs: KTypeAttrs::default(),
+    })
+}
+/// Product-type with fields referencing `field_types` in order.
+fn referring_type(name: &str, field_types: &[&str]) -> KType {
+    prod_type(name, field_types.iter().enumerate().map(|(i,x)| field(&format!("field{}", i), def(x))).collect())
+}
+
+#[test]
+fn test_type_name_collision_lint() {
+    let a = Doc1 {
+        funcs: vec![],
+        types: vec![new_type("String"), new_type("Order"), new_type("ORDER")],
+    };
+    let mut x = Context::default();
+    a.lint(Path::default(), &mut x);
+    println!("{}", x);
+    let paths = x.logs.iter().filter(|x| x.severity == Severity::Error).map(|x| x.path.to_string()).collect::<Vec<_>>();
+    assert_eq!(paths, vec!["types/String", "types/ORDER"]);
+}
+
+#[test]
+fn test_rust_syntax_lint() {
+    let mut rust_type = new_type("Order");
+    if let KType::New(x) = &mut rust_type { x.attrs.rust_type = Some("uuid::Uuid<".to_string()) }
+    let mut derive = new_type("Ship");
+    if let KType::New(x) = &mut derive { x.attrs.derive = vec!["Hash".to_string(), "serde Serialize".to_string()] }
+    let a = Doc1 {
+        funcs: vec![],
+        types: vec![new_type("type"), new_type("my-type"), rust_type, derive],
+    };
+    let mut x = Context::default();
+    a.lint(Path::default(), &mut x);
+    let paths = x.logs.iter().filter(|x| x.severity == Severity::Error).map(|x| x.path.to_string()).collect::<Vec<_>>();
+    assert_eq!(paths, vec!["types/type", "types/my-type", "types/Order/rust_type", "types/Ship/derive/serde Serialize"]);
+}
+
+#[test]
+fn test_type_name_collision_rename() {
+    let mut a = Doc1 {
+        funcs: vec![],
+        types: vec![new_type("String"), new_type("Order"), new_type("ORDER"), referring_type("Ship", &["String", "ORDER"])],
+    };
+    a.rename_conflicting_types("", "1");
+    let names = a.types.iter().map(|x| x.name()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["String1", "Order", "ORDER1", "Ship"]);
+    assert_eq!(a.types[3], referring_type("Ship", &["String1", "ORDER1"]));
+
+    let mut x = Context::default();
+    a.lint(Path::default(), &mut x);
+    x.check().unwrap();
+}
+
+#[test]
+fn test_type_ref_lint() {
+    let a = Doc1 {
+        funcs: vec![],
+        types: vec![referring_type("Ship", &["Cargo", "Crew", "Ship"]), new_type("Cargo"), new_type("Order"), new_type("Fuel")],
+    };
+    let mut x = Context::default();
+    a.lint(Path::default(), &mut x);
+    a.lint_skipped_types(&["Cargo".to_string(), "Sail".to_string()], Path::default(), &mut x);
+    println!("{}", x);
+    let logs = x.logs.iter().map(|x| (x.severity, x.path.to_string())).collect::<Vec<_>>();
+    assert_eq!(logs, vec![
+        (Severity::Error, "types/Ship/field1".to_string()),
+        (Severity::Warning, "types/Ship".to_string()),
+        (Severity::Warning, "types/Order".to_string()),
+        (Severity::Warning, "types/Fuel".to_string()),
+        (Severity::Warning, "types/Sail".to_string()),
+        (Severity::Info, "types/Ship/field0".to_string()),
+    ]);
+}
+
+#[test]
+fn test_recursive_type_edges() {
+    let mut tree = referring_type("Tree", &["Tree"]);
+    if let KType::Prod(x) = &mut tree { x.fields[0].content.array = true }
+    let a = Doc1 {
+        funcs: vec![],
+        types: vec![
+            referring_type("Node", &["Node", "Leaf"]),
+            new_type("Leaf"),
+            referring_type("A", &["B"]),
+            referring_type("B", &["C", "A"]),
+            referring_type("C", &["A", "B"]),
+            tree,
+        ],
+    };
+    let sccs = a.type_sccs();
+    assert!(sccs.contains(&vec!["Node"]));
+    assert!(sccs.contains(&vec!["A", "B", "C"]));
+    let edges = a.recursive_type_edges().iter().map(|x| (x.from, x.member)).collect::<Vec<_>>();
+    assert_eq!(edges, vec![("Node", "field0"), ("A", "field0"), ("B", "field0")]);
+}
+
+#[test]
+fn test_diff() {
+    let fish = |cases: &[&str]| KType::Enum(KEnumType {
+        name: "Fish".to_string(),
+        cases: cases.iter().map(|x| KEnumTypeCase { name: x.to_string(), ..Default::default() }).collect(),
+        comment: "".to_string(),
+        attrs: KTypeAttrs::default(),
+    });
+    let a = Doc1 {
+        funcs: vec![],
+        types: vec![
+            new_type("Order"),
+            fish(&["Whale", "Shrimp"]),
+            prod_type("Ship", vec![
+                field("name", prim(KPrimType::String)),
+                field("speed", prim(KPrimType::F64)).optional(),
+                field("crews", prim(KPrimType::I32)),
+                field("flag", prim(KPrimType::String)).optional(),
+            ]),
+        ],
+    };
+    let b = Doc1 {
+        funcs: vec![],
+        types: vec![
+            fish(&["Whale", "Tuna"]),
+            prod_type("Ship", vec![
+                field("name", prim(KPrimType::String)).optional(),
+                field("speed", prim(KPrimType::F64)),
+                field("crews", prim(KPrimType::String)),
+                field("cargo", def("Fish")).optional(),
+            ]),
+            new_type("Port"),
+        ],
+    };
+    let changes = a.diff(&b, Path::default()).iter().map(|x| x.to_string()).collect::<Vec<_>>();
+    assert_eq!(changes, vec![
+        "breaks readers and writers: types/Order: type removed",
+        "breaks writers: types/Fish/Shrimp: case removed",
+        "breaks readers: types/Fish/Tuna: case added",
+        "breaks readers: types/Ship/name: field made optional",
+        "breaks writers: types/Ship/speed: field made required",
+        "breaks readers and writers: types/Ship/crews: content type changed",
+        "compatible: types/Ship/flag: optional field removed",
+        "compatible: types/Ship/cargo: optional field added",
+        "compatible: types/Port: type added",
+    ]);
+    assert!(a.diff(&a, Path::default()).is_empty());
+}
+
+#[test]
+fn test_sum_type_diff() {
+    let pet = |variants: &[(&str,&str)]| KType::Sum(KSumType {
+        name: "Pet".to_string(),
+        discriminant: "type".to_string(),
+        variants: variants.iter().map(|(name,tag)| KSumTypeVariant {
+            name: name.to_string(),
+            tag: tag.to_string(),
+            content: KContentStorage { r#type: KTypeRef::Prim(KPrimType::String), ..Default::default() },
+            ..Default::default()
+        }).collect(),
+        ..Default::default()
+    });
+    let a = Doc1 { funcs: vec![], types: vec![pet(&[("Cat", "cat"), ("Dog", "dog")])] };
+    let b = Doc1 { funcs: vec![], types: vec![pet(&[("Kitty", "cat"), ("Dog", "hound")])] };
+    let changes = a.diff(&b, Path::default()).iter().map(|x| x.to_string()).collect::<Vec<_>>();
+    // Variants are matched by tag, so renamed variants with same tag are not changes.
+    assert_eq!(changes, vec![
+        "breaks writers: types/Pet/Dog: variant removed",
+        "breaks readers: types/Pet/Dog: variant added",
+    ]);
+}
+
+#[test]
+fn test_constraint_diff() {
+    let order = |constraints: KConstraints| KType::New(KNewType {
+        name: "Order".to_string(),
+        origin: KTypeRef::Prim(KPrimType::String),
+        constraints,
+        comment: "".to_string(),
+        attrs: KTypeAttrs::default(),
+    });
+    let diff = |a: KConstraints, b: KConstraints| {
+        let a = Doc1 { funcs: vec![], types: vec![order(a)] };
+        let b = Doc1 { funcs: vec![], types: vec![order(b)] };
+        a.diff(&b, Path::default()).iter().map(|x| x.to_string()).collect::<Vec<_>>()
+    };
+    let a = KConstraints {
+        minimum: Some(1.into()),
+        maximum: Some(10.into()),
+        min_length: Some(2),
+        max_length: Some(20),
+        pattern: Some("^[a-z]+$".to_string()),
+        min_items: Some(1),
+        max_items: Some(5),
+        unique_items: false,
+    };
+    assert!(diff(a.clone(), a.clone()).is_empty());
+    let tight = KConstraints {
+        minimum: Some(2.into()),
+        maximum: Some(9.into()),
+        min_length: Some(3),
+        max_length: Some(19),
+        pattern: Some("^[a-c]+$".to_string()),
+        min_items: Some(2),
+        max_items: Some(4),
+        unique_items: true,
+    };
+    assert_eq!(diff(a.clone(), tight), vec![
+        "breaks writers: types/Order/minimum: constraint tightened",
+        "breaks writers: types/Order/maximum: constraint tightened",
+        "breaks writers: types/Order/minLength: constraint tightened",
+        "breaks writers: types/Order/maxLength: constraint tightened",
+        "breaks writers: types/Order/pattern: constraint tightened",
+        "breaks writers: types/Order/minItems: constraint tightened",
+        "breaks writers: types/Order/maxItems: constraint tightened",
+        "breaks writers: types/Order/uniqueItems: constraint tightened",
+    ]);
+    let loose = KConstraints {
+        minimum: Some(0.into()),
+        maximum: Some(11.into()),
+        min_length: Some(1),
+        max_length: None,
+        pattern: None,
+        min_items: Some(0),
+        max_items: Some(6),
+        unique_items: false,
+    };
+    assert_eq!(diff(a.clone(), loose), vec![
+        "compatible: types/Order/minimum: constraint loosened",
+        "compatible: types/Order/maximum: constraint loosened",
+        "compatible: types/Order/minLength: constraint loosened",
+        "compatible: types/Order/maxLength: constraint loosened",
+        "compatible: types/Order/pattern: constraint loosened",
+        "compatible: types/Order/minItems: constraint loosened",
+        "compatible: types/Order/maxItems: constraint loosened",
+    ]);
+    // Added constraints are tightening.
+    assert_eq!(diff(KConstraints::default(), KConstraints { max_length: Some(20), pattern: Some("^a".to_string()), ..Default::default() }), vec![
+        "breaks writers: types/Order/maxLength: constraint tightened",
+        "breaks writers: types/Order/pattern: constraint tightened",
+    ]);
+}
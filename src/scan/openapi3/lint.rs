@@ -10,6 +10,7 @@ impl Lint for oa::Doc {
             None => context.error(path.appending("components"), "missing required property `components`"),
             Some(x) => x.lint(path.appending("components"), context),
         };
+        context.guard_extensions(&self.extensions, path.clone(), false);
     }
 }
 
@@ -21,8 +22,16 @@ impl Lint for oa::Components {
                 for (name,schema) in x {
                     schema.lint(path.appending(&name), context);
                 }
+                for (name,schema) in x {
+                    if let oa::ReferencedOrInlineSchema::Inline(schema) = schema {
+                        if let Some(to) = schema.x_kcg_rename.as_ref() {
+                            context.guard(!x.contains_key(to), path.appending(name).appending("x-kcg-rename"), "renamed name must not be a name of another schema");
+                        }
+                    }
+                }
             },
         }
+        context.guard_extensions(&self.extensions, path.clone(), false);
     }
 }
 
@@ -42,8 +51,17 @@ impl Lint for oa::Schema {
         // KCG accepts only certain pattern of Schema object that are mapped to KCG types.
         // Everything else will be rejected.
         context.guard_nil_property_for_unsupported_feature(&self.any_of, path.appending("anyOf"), "property for unsupported feature has non-`nil` value");
-        context.guard_nil_property_for_unsupported_feature(&self.format, path.appending("format"), "property for unsupported feature has non-`nil` value");
         context.guard_nil_property_for_unsupported_feature(&self.not, path.appending("not"), "property for unsupported feature has non-`nil` value");
+        context.guard_extensions(&self.extensions, path.clone(), true);
+        self.lint_kcg_extensions(path.clone(), context);
+        self.lint_constraints(path.clone(), context);
+        context.guard(!self.nullable, path.appending("nullable"), "must be `false` for named types (only properties can be nullable)");
+        if self.r#enum.is_none() {
+            context.guard_nil_property(&self.x_enum_descriptions, path.appending("x-enum-descriptions"), "must be `nil` unless `enum` is defined");
+            context.guard_nil_property(&self.x_enum_varnames, path.appending("x-enum-varnames"), "must be `nil` unless `enum` is defined");
+            context.guard_nil_property(&self.x_kcg_deprecated_cases, path.appending("x-kcg-deprecated-cases"), "must be `nil` unless `enum` is defined");
+        }
+        self.lint_deprecation(path.clone(), context);
         match (self.r#type.str(), self.is_prim_type(), &self.one_of, &self.r#enum) {
             (_, true, None, None) => self.lint_new_type(path.clone(), context),
             (_, true, None, Some(_)) => self.lint_enum_type(path.clone(), context),
@@ -54,12 +72,86 @@ impl Lint for oa::Schema {
     }
 }
 impl oa::Schema {
+    fn lint_kcg_extensions(&self, path: Path, context: &mut Context) {
+        if let Some(x) = self.x_kcg_rename.as_ref() {
+            context.guard(is_identifier(x), path.appending("x-kcg-rename"), "must be a valid identifier");
+        }
+        if let Some(x) = self.x_kcg_rust_type.as_ref() {
+            context.guard(syn::parse_str::<syn::Type>(x).is_ok(), path.appending("x-kcg-rust-type"), "must be a valid Rust type expression");
+        }
+        for x in self.x_kcg_derive.iter().flatten() {
+            context.guard(is_identifier(x), path.appending("x-kcg-derive").appending(x), "must be a valid trait name");
+        }
+    }
+    /// Checks whether constraint keywords fit to the type.
+    fn lint_constraints(&self, path: Path, context: &mut Context) {
+        let (number, string, array) = match self.r#type.str() {
+            "integer" | "number" => (true, false, false),
+            "string" => (false, true, false),
+            "array" => (false, false, true),
+            _ => (false, false, false),
+        };
+        if !number {
+            context.guard_nil_property(&self.minimum, path.appending("minimum"), "must be `nil` unless `type` is `integer` or `number`");
+            context.guard_nil_property(&self.maximum, path.appending("maximum"), "must be `nil` unless `type` is `integer` or `number`");
+        }
+        if !string || self.is_formatted_string() {
+            context.guard_nil_property(&self.min_length, path.appending("minLength"), "must be `nil` unless `type` is `string` with no KCG supported `format`");
+            context.guard_nil_property(&self.max_length, path.appending("maxLength"), "must be `nil` unless `type` is `string` with no KCG supported `format`");
+            context.guard_nil_property(&self.pattern, path.appending("pattern"), "must be `nil` unless `type` is `string` with no KCG supported `format`");
+        }
+        if !array {
+            context.guard_nil_property(&self.min_items, path.appending("minItems"), "must be `nil` unless `type` is `array`");
+            context.guard_nil_property(&self.max_items, path.appending("maxItems"), "must be `nil` unless `type` is `array`");
+            context.guard(!self.unique_items, path.appending("uniqueItems"), "must be `false` unless `type` is `array`");
+        }
+        if let (Some(a), Some(b)) = (self.minimum.as_ref().and_then(|x| x.as_f64()), self.maximum.as_ref().and_then(|x| x.as_f64())) {
+            context.guard(a <= b, path.appending("minimum"), "must not be greater than `maximum`");
+        }
+        if let (Some(a), Some(b)) = (self.min_length, self.max_length) {
+            context.guard(a <= b, path.appending("minLength"), "must not be greater than `maxLength`");
+        }
+        if let (Some(a), Some(b)) = (self.min_items, self.max_items) {
+            context.guard(a <= b, path.appending("minItems"), "must not be greater than `maxItems`");
+        }
+        if let Some(oa::ReferencedOrInlineSchema::Inline(x)) = self.items.as_deref() {
+            x.lint_constraints(path.appending("items"), context);
+        }
+    }
+    /// Checks whether `value` fits to the type of this property.
+    /// Arrays of referenced types can have only empty default.
+    fn is_valid_default(&self, value: &serde_json::Value) -> bool {
+        use serde_json::Value;
+        match (self.r#type.str(), self.format.str(), value) {
+            ("boolean", _, Value::Bool(_)) => true,
+            ("integer", "int32", Value::Number(x)) => x.as_i64().map(|x| i32::try_from(x).is_ok()).unwrap_or(false),
+            ("integer", _, Value::Number(x)) => x.is_i64(),
+            ("number", _, Value::Number(_)) => true,
+            ("string", _, Value::String(_)) => !self.is_formatted_string(),
+            ("array", _, Value::Array(xs)) => match self.items.as_deref() {
+                Some(oa::ReferencedOrInlineSchema::Inline(item)) => xs.iter().all(|x| item.is_valid_default(x)),
+                _ => xs.is_empty(),
+            },
+            _ => false,
+        }
+    }
     fn lint_new_type(&self, path: Path, context: &mut Context) {
         context.guard_nil_property(&self.items, path.appending("items"), "must be `nil` to make new-type");
         context.guard_nil_property(&self.all_of, path.appending("allOf"), "must be `nil` to make new-type");
-        context.guard_nil_property(&self.format, path.appending("format"), "must be `nil` to make new-type");
+        self.lint_string_format(path.clone(), context);
         context.guard_nil_property(&self.any_of, path.appending("anyOf"), "must be `nil` to make new-type");
         context.guard_nil_property(&self.properties, path.appending("properties"), "must be `nil` to make new-type");
+        context.guard_nil_property(&self.x_kcg_tagging, path.appending("x-kcg-tagging"), "must be `nil` to make new-type");
+    }
+    /// Checks `format` of a string.
+    /// Unknown formats are allowed as annotations, and the string is treated as a plain string.
+    fn lint_string_format(&self, path: Path, context: &mut Context) {
+        match (self.r#type.str(), self.format.str()) {
+            (_, "") => (),
+            ("string", x) if oa::STRING_FORMATS.contains(&x) => (),
+            ("string", _) => context.warning(path.appending("format"), UNKNOWN_STRING_FORMAT),
+            (_, _) => context.error(path.appending("format"), "must be `nil` unless `type` is `string`"),
+        }
     }
     fn lint_enum_type(self: &Self, path: Path, context: &mut Context) {
         context.guard_nil_property(&self.items, path.appending("items"), "must be `nil` to make new-type");
@@ -67,6 +159,21 @@ impl oa::Schema {
         context.guard_nil_property(&self.format, path.appending("format"), "must be `nil` to make new-type");
         context.guard_nil_property(&self.any_of, path.appending("anyOf"), "must be `nil` to make new-type");
         context.guard_nil_property(&self.properties, path.appending("properties"), "must be `nil` to make new-type");
+        context.guard_nil_property(&self.x_kcg_tagging, path.appending("x-kcg-tagging"), "must be `nil` to make new-type");
+        let count = self.r#enum.as_ref().map(|x| x.len()).unwrap_or_default();
+        if let Some(x) = self.x_enum_descriptions.as_ref() {
+            context.guard(x.len() == count, path.appending("x-enum-descriptions"), "must have same number of items with `enum`");
+        }
+        if let Some(x) = self.x_enum_varnames.as_ref() {
+            context.guard(x.len() == count, path.appending("x-enum-varnames"), "must have same number of items with `enum`");
+            for (i,x) in x.iter().enumerate() {
+                context.guard(is_identifier(x), path.appending("x-enum-varnames").appending(&i.to_string()), "must be a valid identifier");
+            }
+        }
+        for (i,x) in self.x_kcg_deprecated_cases.iter().flatten().enumerate() {
+            let case = serde_json::Value::String(x.clone());
+            context.guard(self.r#enum.iter().flatten().any(|x| *x == case), path.appending("x-kcg-deprecated-cases").appending(&i.to_string()), "must be one of `enum` cases");
+        }
     }
     fn lint_sum_type(self: &Self, path: Path, context: &mut Context) {
         context.guard_nil_property(&self.items, path.appending("items"), "must be `nil` to make sum-type");
@@ -75,10 +182,46 @@ impl oa::Schema {
         context.guard_nil_property(&self.properties, path.appending("properties"), "must be `nil` to make sum-type");
         context.guard_nil_property(&self.r#enum, path.appending("enum"), "must be `nil` to make sum-type");
         context.guard_some_property(&self.one_of, path.appending("oneOf"), "must be non-`nil` to make sum-type");
-        context.guard_some_property(&self.discriminator, path.appending("discriminator"), "must be non-`nil` to make sum-type");
+        for (i,x) in self.one_of.iter().flatten().enumerate() {
+            if let oa::ReferencedOrInlineSchema::Referenced(x) = x {
+                x.lint(path.appending("oneOf").appending(&i.to_string()), context);
+            }
+        }
+        self.lint_sum_type_tagging(path.clone(), context);
         match self.discriminator.as_ref() {
             None => (),
-            Some(det) => det.lint(path.appending("discriminator"), context),
+            Some(det) => {
+                det.lint(path.appending("discriminator"), context);
+                self.lint_sum_type_mapping(det, path.appending("discriminator"), context);
+            },
+        }
+    }
+    fn lint_sum_type_tagging(&self, path: Path, context: &mut Context) {
+        match self.x_kcg_tagging.str() {
+            "internal" => context.guard_some_property(&self.discriminator, path.appending("discriminator"), "must be non-`nil` to make internally tagged sum-type"),
+            "adjacent" => {
+                context.guard_some_property(&self.discriminator, path.appending("discriminator"), "must be non-`nil` to make adjacently tagged sum-type");
+                context.guard_some_property(&self.x_kcg_content, path.appending("x-kcg-content"), "must be non-`nil` to make adjacently tagged sum-type");
+            },
+            "external" | "untagged" | "" => (),
+            _ => context.error(path.appending("x-kcg-tagging"), "must be one of `internal`, `external`, `adjacent` or `untagged`"),
+        }
+        if self.x_kcg_tagging.str() != "adjacent" {
+            context.guard_nil_property(&self.x_kcg_content, path.appending("x-kcg-content"), "must be `nil` unless sum-type is adjacently tagged");
+        }
+    }
+    fn lint_sum_type_mapping(&self, det: &oa::Discriminator, path: Path, context: &mut Context) {
+        let variant_names = self.one_of.iter().flatten()
+            .filter_map(|x| match x { oa::ReferencedOrInlineSchema::Referenced(x) => Some(x), _ => None })
+            .filter_map(|x| x.r#ref.rsplit('/').next())
+            .collect::<Vec<_>>();
+        let mut mapped_names = Vec::<&str>::new();
+        for (tag,target) in det.mapping.iter().flatten() {
+            let name = target.rsplit('/').next().unwrap_or("");
+            context.guard(variant_names.contains(&name), path.appending("mapping").appending(tag), "mapping target must be one of `oneOf` references");
+            // A variant is serialized with single tag, so other tags would be lost.
+            context.guard(!mapped_names.contains(&name), path.appending("mapping").appending(tag), "mapping target must not be mapped by another tag");
+            mapped_names.push(name);
         }
     }
     fn lint_prod_type(self: &Self, path: Path, context: &mut Context) {
@@ -89,18 +232,85 @@ impl oa::Schema {
         context.guard_nil_property(&self.discriminator, path.appending("discriminator"), "must be non-`nil` to make prod-type");
         context.guard_nil_property(&self.r#enum, path.appending("enum"), "must be non-`nil` to make prod-type");
         context.guard_some_property(&self.properties, path.appending("properties"), "must be non-`nil` to make prod-type");
+        context.guard_nil_property(&self.x_kcg_tagging, path.appending("x-kcg-tagging"), "must be `nil` to make prod-type");
         for x in self.properties.iter() {
             for (name,xx) in x {
                 use oa::ReferencedOrInlineSchema::*;
                 match xx {
-                    Referenced(_) => (),
+                    Referenced(xxx) => xxx.lint(path.appending(name), context),
+                    Inline(xxx) if xxx.wrapped_reference().is_some() => xxx.lint_wrapped_reference(path.appending(name), context),
                     Inline(xxx) => xxx.lint_property(path.appending(name), context),
                 }
             }
             break
         }
     }
+    /// Checks a reference wrapped in `allOf` only to be annotated.
+    fn lint_wrapped_reference(&self, path: Path, context: &mut Context) {
+        if let Some(x) = self.wrapped_reference() {
+            x.lint(path.appending("allOf").appending("0"), context);
+        }
+        context.guard_nil_property(&self.r#type, path.appending("type"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.items, path.appending("items"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.properties, path.appending("properties"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.one_of, path.appending("oneOf"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.r#enum, path.appending("enum"), "must be `nil` to annotate a reference with `allOf`");
+        // Constraints and other keywords are not carried over to the reference, so they are rejected instead of being dropped.
+        context.guard_nil_property(&self.required, path.appending("required"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.format, path.appending("format"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard(*self.additional_properties == oa::AdditionalProperties::Bool(false), path.appending("additionalProperties"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.minimum, path.appending("minimum"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.maximum, path.appending("maximum"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.min_length, path.appending("minLength"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.max_length, path.appending("maxLength"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.pattern, path.appending("pattern"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.min_items, path.appending("minItems"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.max_items, path.appending("maxItems"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard(!self.unique_items, path.appending("uniqueItems"), "must be `false` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.discriminator, path.appending("discriminator"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.any_of, path.appending("anyOf"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.not, path.appending("not"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.x_enum_descriptions, path.appending("x-enum-descriptions"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.x_enum_varnames, path.appending("x-enum-varnames"), "must be `nil` to annotate a reference with `allOf`");
+        context.guard_nil_property(&self.x_kcg_deprecated_cases, path.appending("x-kcg-deprecated-cases"), "must be `nil` to annotate a reference with `allOf`");
+        // Values of referenced types cannot be written in code, but `null` can.
+        if let Some(x) = self.default.as_ref() {
+            context.guard(self.nullable && x.is_null(), path.appending("default"), "must be `nil` or `null` of nullable reference annotated with `allOf`");
+        }
+        self.lint_deprecation(path.clone(), context);
+        self.lint_member_extensions(path.clone(), context);
+    }
+    /// Checks extensions of a schema defining a member rather than a named type.
+    /// Unmodeled properties are rejected, and type-only KCG extensions are meaningless here.
+    fn lint_member_extensions(&self, path: Path, context: &mut Context) {
+        context.guard_extensions(&self.extensions, path.clone(), true);
+        context.guard(!self.x_kcg_skip, path.appending("x-kcg-skip"), "must be `nil` unless schema defines a named type");
+        context.guard_nil_property(&self.x_kcg_rename, path.appending("x-kcg-rename"), "must be `nil` unless schema defines a named type");
+        context.guard_nil_property(&self.x_kcg_rust_type, path.appending("x-kcg-rust-type"), "must be `nil` unless schema defines a named type");
+        context.guard_nil_property(&self.x_kcg_derive, path.appending("x-kcg-derive"), "must be `nil` unless schema defines a named type");
+        context.guard_nil_property(&self.x_kcg_tagging, path.appending("x-kcg-tagging"), "must be `nil` unless schema defines a named type");
+        context.guard_nil_property(&self.x_kcg_content, path.appending("x-kcg-content"), "must be `nil` unless schema defines a named type");
+    }
+    fn lint_deprecation(&self, path: Path, context: &mut Context) {
+        if !self.deprecated {
+            context.guard_nil_property(&self.x_kcg_deprecation_note, path.appending("x-kcg-deprecation-note"), "must be `nil` unless `deprecated` is `true`");
+        }
+    }
     fn lint_property(&self, path: Path, context: &mut Context) {
+        context.guard_nil_property(&self.all_of, path.appending("allOf"), "must be `nil` unless it wraps a single reference");
+        self.lint_member_extensions(path.clone(), context);
+        self.lint_constraints(path.clone(), context);
+        self.lint_deprecation(path.clone(), context);
+        if let Some(x) = self.default.as_ref() {
+            context.guard(self.is_valid_default(x) || (self.nullable && x.is_null()), path.appending("default"), "must be a value of property type");
+        }
+        if let Some(oa::ReferencedOrInlineSchema::Referenced(x)) = self.items.as_deref() {
+            x.lint(path.appending("items"), context);
+        }
+        if let Some(oa::ReferencedOrInlineSchema::Inline(x)) = self.items.as_deref() {
+            context.guard(!x.nullable, path.appending("items").appending("nullable"), "nullable array items are not supported");
+            x.lint_member_extensions(path.appending("items"), context);
+        }
         if self.is_prim_type() {
             match (self.r#type.str(), self.format.str()) {
                 ("boolean", "") => (),
@@ -111,7 +321,7 @@ impl oa::Schema {
                 ("number", "float") => (),
                 ("number", "double") => (),
                 ("number", _) => context.error(path, "`number` type property must define `format` to one of `float` or `double`"),
-                ("string", _) => (),
+                ("string", _) => self.lint_string_format(path, context),
                 ("array", _) => (),
                 (_,_) => context.error(path, "`type` must be set to one of `boolean`, `integer`, `number`, `array` (inline `object` definition is not supported)"),
             }
@@ -123,7 +333,11 @@ impl oa::Schema {
 }
 impl Lint for oa::Discriminator {
     fn lint<'a>(self: &Self, path: Path, context: &mut Context) {
-        context.guard_nil_property_for_unsupported_feature(&self.mapping, path.appending("propertyName"), "must be non-`nil` to make sum-type");
+        for (tag,target) in self.mapping.iter().flatten() {
+            let subpath = path.appending("mapping").appending(tag);
+            context.guard(!target.contains('/') || target.starts_with("#/components/schemas/"), subpath.clone(), "mapping target must be a schema name or `#/components/schemas` prefixed reference");
+            context.guard(!target.ends_with('/'), subpath.clone(), "mapping target must be a schema name or `#/components/schemas` prefixed reference");
+        }
     }
 }
 
@@ -134,7 +348,11 @@ impl Lint for oa::Reference {
         context.guard(!x.ends_with("/"), path.appending("$ref"), "JSON Schema reference expression must starts with `#/`");
         context.guard(x.starts_with("#/components/schemas/"), path.appending("$ref"), "KCG supports only `#/components/schemas` prefixed reference");
         context.guard(x.split_at("#/components/schemas/".len()).1.contains("/") == false, path.appending("$ref"), "KCG does not support nested paths");
-        
+        // Siblings of `$ref` are ignored by OpenAPI 3.0, so `nullable` and others would be dropped silently.
+        for (name,_) in self.extensions.iter().filter(|(x,_)| !x.starts_with("x-")) {
+            context.error(path.appending(name), "must be `nil` next to `$ref` (wrap reference in `allOf` to annotate it)");
+        }
+        context.guard_extensions(&self.extensions, path.clone(), false);
     }
 }
 
@@ -165,6 +383,27 @@ impl Context {
     fn guard(self: &mut Self, condition: bool, path: Path, message: &'static str) {
         if !condition { self.error(path, message) }
     }
+    /// Checks properties not modeled by KCG.
+    /// Unknown KCG extensions are always rejected.
+    /// - `strict`: Rejects everything but vendor extensions.
+    fn guard_extensions(&mut self, extensions: &oa::Map<String,serde_json::Value>, path: Path, strict: bool) {
+        for (name,_) in extensions {
+            if name.starts_with("x-kcg-") {
+                self.error(path.appending(name), "unknown KCG extension");
+            }
+            else if strict && !name.starts_with("x-") {
+                self.error(path.appending(name), "unknown/unsupported property");
+            }
+        }
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(x) if x.is_ascii_alphabetic() || x == '_' => chars.all(|x| x.is_ascii_alphanumeric() || x == '_'),
+        _ => false,
+    }
 }
 
 
@@ -25,6 +25,10 @@ pub struct Doc {
     pub security: Option<List<SecurityRequirement>>,
     pub tags: Option<List<Tag>>,
     pub external_docs: Option<ExternalDocumentation>,
+    /// Properties not modeled here.
+    /// `x-` prefixed ones are vendor extensions.
+    #[serde(flatten)]
+    pub extensions: Map<String,serde_json::Value>,
 }
 
 #[derive(Eq, PartialEq)]
@@ -93,6 +97,10 @@ pub struct Paths {
 #[serde(rename_all="camelCase")]
 pub struct Components {
     pub schemas: Option<Map<String,ReferencedOrInlineSchema>>,
+    /// Properties not modeled here.
+    /// `x-` prefixed ones are vendor extensions.
+    #[serde(flatten)]
+    pub extensions: Map<String,serde_json::Value>,
 }
 
 #[derive(Eq, PartialEq)]
@@ -116,7 +124,8 @@ pub struct Tag {
 #[derive(Debug)]
 #[serde(rename_all="camelCase")]
 pub struct ExternalDocumentation {
-
+    pub description: Option<String>,
+    pub url: String,
 }
 
 /// OpenAPI 3.0 Schema object.
@@ -124,10 +133,10 @@ pub struct ExternalDocumentation {
 ///   - https://json-schema.org/specification.html
 /// - Only certain strict subset will be supported.
 /// - Any unsupported properties for unsupported features will be rejected.
+/// - Recognized `x-kcg-*` vendor extensions are modeled as `x_kcg_*` properties.
 #[derive(Eq, PartialEq)]
 #[derive(Serialize, Deserialize)]
 #[derive(Debug)]
-#[serde(deny_unknown_fields)]
 #[serde(rename_all="camelCase")]
 pub struct Schema {
     pub title:  Option<String>,
@@ -147,12 +156,67 @@ pub struct Schema {
     pub additional_properties: Box<AdditionalProperties>,
     pub description: Option<String>,
     pub format: Option<String>,
+    /// `Some(Null)` for `default: null`, which is distinguished from absent `default`.
+    #[serde(default, deserialize_with="deserialize_present")]
     pub default: Option<serde_json::Value>,
 
+    pub minimum: Option<serde_json::Number>,
+    pub maximum: Option<serde_json::Number>,
+    pub min_length: Option<u64>,
+    pub max_length: Option<u64>,
+    pub pattern: Option<String>,
+    pub min_items: Option<u64>,
+    pub max_items: Option<u64>,
+    #[serde(default)]
+    pub unique_items: bool,
+
     pub discriminator: Option<Discriminator>,
+    /// KCG extension to choose sum-type serialization form.
+    /// One of `internal`, `external`, `adjacent` or `untagged`.
+    #[serde(rename="x-kcg-tagging")]
+    pub x_kcg_tagging: Option<String>,
+    /// KCG extension to name content field of adjacently tagged sum-type.
+    #[serde(rename="x-kcg-content")]
+    pub x_kcg_content: Option<String>,
+    /// Descriptions of `enum` cases in same order.
+    /// A widely used vendor extension.
+    #[serde(rename="x-enum-descriptions")]
+    pub x_enum_descriptions: Option<List<String>>,
+    /// Names of `enum` cases in generated code in same order.
+    /// A widely used vendor extension.
+    #[serde(rename="x-enum-varnames")]
+    pub x_enum_varnames: Option<List<String>>,
     pub example: Option<serde_json::Value>,
+    pub external_docs: Option<ExternalDocumentation>,
     #[serde(default)]
     pub deprecated: bool,
+    /// KCG extension to describe deprecation of this schema.
+    #[serde(rename="x-kcg-deprecation-note")]
+    pub x_kcg_deprecation_note: Option<String>,
+    /// KCG extension to deprecate certain `enum` cases.
+    #[serde(rename="x-kcg-deprecated-cases")]
+    pub x_kcg_deprecated_cases: Option<List<String>>,
+    #[serde(default)]
+    pub nullable: bool,
+
+    /// KCG extension to skip code-gen for this type.
+    /// You are supposed to provide type definition yourself.
+    #[serde(rename="x-kcg-skip")]
+    #[serde(default)]
+    pub x_kcg_skip: bool,
+    /// KCG extension to rename this type in generated code.
+    #[serde(rename="x-kcg-rename")]
+    pub x_kcg_rename: Option<String>,
+    /// KCG extension to alias this type to a Rust type instead of generating definition.
+    #[serde(rename="x-kcg-rust-type")]
+    pub x_kcg_rust_type: Option<String>,
+    /// KCG extension to add derives to this type.
+    #[serde(rename="x-kcg-derive")]
+    pub x_kcg_derive: Option<List<String>>,
+    /// Properties not modeled here.
+    /// Lint rejects anything but vendor extensions.
+    #[serde(flatten)]
+    pub extensions: Map<String,serde_json::Value>,
 }
 impl Schema {
     pub fn is_prim_type(&self) -> bool {
@@ -164,7 +228,26 @@ impl Schema {
             None => false,
         }
     }
+    /// Reference wrapped in `allOf` to annotate a reference. `allOf: [$ref: ...]`
+    /// `None` if this is not such a wrapper.
+    pub fn wrapped_reference(&self) -> Option<&Reference> {
+        match self.all_of.as_deref() {
+            Some([ReferencedOrInlineSchema::Referenced(x)]) => Some(x),
+            _ => None,
+        }
+    }
+    /// Whether this is a string with a format KCG maps to dedicated types.
+    /// Strings with other formats are treated as plain strings.
+    pub fn is_formatted_string(&self) -> bool {
+        self.r#type.as_deref() == Some("string") && STRING_FORMATS.contains(&self.format.as_deref().unwrap_or(""))
+    }
 }
+/// Deserializes a present value including `null` into `Some`.
+fn deserialize_present<'de,D:serde::Deserializer<'de>>(deserializer: D) -> Result<Option<serde_json::Value>,D::Error> {
+    <serde_json::Value as serde::Deserialize>::deserialize(deserializer).map(Some)
+}
+/// String formats supported by KCG.
+pub const STRING_FORMATS: &[&str] = &["date-time", "date", "uuid", "uri", "byte", "binary"];
 
 /// When request bodies or response payloads may be one of a number of different schemas, a discriminator object can be used to aid in serialization, deserialization, and validation. The discriminator is a specific object in a schema which is used to inform the consumer of the specification of an alternative schema based on the value associated with it.
 /// When using the discriminator, inline schemas will not be considered.
@@ -211,6 +294,14 @@ impl ReferencedOrInlineSchema {
 pub struct Reference {
     #[serde(rename="$ref")]
     pub r#ref: String,
+    /// Overrides summary of referenced schema. (OpenAPI 3.1)
+    pub summary: Option<String>,
+    /// Overrides description of referenced schema. (OpenAPI 3.1)
+    pub description: Option<String>,
+    /// Sibling properties not modeled here. (e.g. `nullable`)
+    /// Lint rejects anything but vendor extensions.
+    #[serde(flatten)]
+    pub extensions: Map<String,serde_json::Value>,
 }
 
 #[derive(Eq, PartialEq)]
@@ -11,10 +11,39 @@ impl oa::Doc {
         let mut k = Doc1::default();
         for comps in self.components.iter() {
             k.types.extend(comps.scan_types(path.appending("components"))?);
+            for (from,to) in comps.scan_type_renames() {
+                k.rename_type(from, to);
+            }
         }
+        scan_unit_variants(&mut k.types);
         Ok(k)
     }
 }
+/// Makes variants referencing empty prod-types unit variants. `{"type": "Ping"}`
+/// Only internally tagged sum-types get unit variants as their serialized forms are same.
+/// Empty prod-types referenced only by unit variants are removed as nothing uses them.
+fn scan_unit_variants(types: &mut Vec<KType>) {
+    let empties = types.iter().filter_map(|x| match x {
+        KType::Prod(x) if x.fields.is_empty() && x.attrs.rust_type.is_none() => Some(KTypeRef::Def(x.name.clone())),
+        _ => None,
+    }).collect::<Vec<_>>();
+    let mut markers = Vec::<KTypeRef>::new();
+    for x in types.iter_mut() {
+        if let KType::Sum(x) = x {
+            if x.tagging != KSumTypeTagging::Internal { continue }
+            for x in x.variants.iter_mut() {
+                if empties.contains(&x.content.r#type) {
+                    markers.push(std::mem::replace(&mut x.content.r#type, KTypeRef::Unit));
+                }
+            }
+        }
+    }
+    let referenced = types.iter().flat_map(|x| x.type_refs().into_iter().map(|(_,r)| r.clone())).collect::<Vec<_>>();
+    types.retain(|x| {
+        let r = KTypeRef::Def(x.name().to_string());
+        !markers.contains(&r) || referenced.contains(&r)
+    });
+}
 impl oa::Components {
     fn scan_types(&self, path: lint::Path) -> Result<Vec<KType>> {
         use oa::ReferencedOrInlineSchema::*;
@@ -30,11 +59,25 @@ impl oa::Components {
         Ok(z)
     }
 }
+impl oa::Components {
+    /// Scans `x-kcg-rename` extensions.
+    /// Renaming is applied after all types are scanned to keep references consistent.
+    fn scan_type_renames(&self) -> Vec<(&str,&str)> {
+        use oa::ReferencedOrInlineSchema::*;
+        let mut z = Vec::new();
+        for (name,x) in self.schemas.iter().flatten() {
+            if let Inline(x) = x {
+                if let Some(to) = x.x_kcg_rename.as_ref() { z.push((name.as_str(), to.as_str())) }
+            }
+        }
+        z
+    }
+}
 impl oa::ReferencedOrInlineSchema {
     fn scan_type_ref(&self, path: lint::Path) -> Result<KTypeRef> {
         use oa::ReferencedOrInlineSchema::*;
         Ok(match self {
-            Referenced(x) => KTypeRef::Def(x.r#ref.clone()),
+            Referenced(x) => KTypeRef::Def(x.scan_referenced_type_name(path).to_string()),
             Inline(x) => KTypeRef::Prim(x.scan_prim_type(path)?),
         })
     }
@@ -55,21 +98,28 @@ impl oa::Schema {
         Ok(KNewType {
             name: name.to_string(),
             origin: KTypeRef::Prim(self.scan_prim_type(path.clone())?), 
+            constraints: self.scan_constraints(path.clone()),
             comment: self.scan_composed_comment(path.clone()),
+            attrs: self.scan_type_attrs(path.clone()),
         })
     }
     fn scan_enum_type(&self, path: lint::Path, name: &str) -> Result<KEnumType> {
         if self.r#type.str() != "string" { return err(&path, "enum-type must be JSON String form (we do not support non-string new-types)") }
         let mut cases = Vec::new(); 
         for xx in self.r#enum.iter() {
-            for x in xx {
+            for (i,x) in xx.iter().enumerate() {
                 let case = match x {
                     serde_json::Value::String(case) => case,
                     _ => return err(&path, "enum-type case must be JSON String type (no support for other types)"),
                 };
                 cases.push(KEnumTypeCase {
                     name: case.to_string(),
-                    comment: "".to_string(),
+                    var_name: self.x_enum_varnames.as_ref().and_then(|x| x.get(i)).cloned(),
+                    comment: self.x_enum_descriptions.as_ref().and_then(|x| x.get(i)).cloned().unwrap_or_default(),
+                    deprecated: match self.x_kcg_deprecated_cases.iter().flatten().any(|x| x == case) {
+                        true => Some(String::new()),
+                        false => None,
+                    },
                 });
             }
             break;
@@ -77,22 +127,53 @@ impl oa::Schema {
         Ok(KEnumType {
             name: name.to_string(),
             cases: cases,
-            comment: self.scan_composed_comment(path),
+            comment: self.scan_composed_comment(path.clone()),
+            attrs: self.scan_type_attrs(path.clone()),
         })
     }
     fn scan_sum_type(&self, path: lint::Path, name: &str) -> Result<KSumType> {
         if self.r#type.str() != "object" { return err(&path, "sum-type must be JSON Object form") }
+        let tagging = self.scan_sum_type_tagging(path.clone())?;
+        let discriminant = match tagging {
+            KSumTypeTagging::Internal | KSumTypeTagging::Adjacent { .. } => self.scan_sum_type_discriminat(path.clone())?,
+            KSumTypeTagging::External | KSumTypeTagging::Untagged => String::new(),
+        };
         Ok(KSumType {
             name: name.to_string(),
-            discriminant: self.scan_sum_type_discriminat(path.clone())?,
+            discriminant,
+            tagging,
             variants: self.scan_sum_type_variants(path.clone())?,
             comment: self.scan_composed_comment(path.clone()),
+            attrs: self.scan_type_attrs(path.clone()),
         })
     }
     fn scan_sum_type_discriminat(&self, path: lint::Path) -> Result<String> {
         let det = self.discriminator.guard(&path.appending("discriminator"), "missing `discriminator` object")?;
         Ok(det.property_name.clone())
     }
+    /// Picks sum-type serialization form.
+    /// `x-kcg-tagging` extension takes precedence over schema patterns.
+    /// - Internally tagged if `discriminator` is defined.
+    /// - Externally tagged if all variants are name-based.
+    /// - Untagged otherwise.
+    fn scan_sum_type_tagging(&self, path: lint::Path) -> Result<KSumTypeTagging> {
+        type KK = oa::ReferencedOrInlineSchema;
+        let x = match self.x_kcg_tagging.str() {
+            "internal" => KSumTypeTagging::Internal,
+            "external" => KSumTypeTagging::External,
+            "adjacent" => KSumTypeTagging::Adjacent {
+                content: self.x_kcg_content.guard(&path.appending("x-kcg-content"), "adjacently tagged sum-type must define content field name")?.clone(),
+            },
+            "untagged" => KSumTypeTagging::Untagged,
+            "" => match (&self.discriminator, self.one_of.iter().flatten().all(KK::is_inline)) {
+                (Some(_), _) => KSumTypeTagging::Internal,
+                (None, true) => KSumTypeTagging::External,
+                (None, false) => KSumTypeTagging::Untagged,
+            },
+            _ => return err(&path.appending("x-kcg-tagging"), "unknown sum-type tagging (must be one of `internal`, `external`, `adjacent` or `untagged`)"),
+        };
+        Ok(x)
+    }
     fn scan_sum_type_variants(&self, path: lint::Path) -> Result<Vec<KSumTypeVariant>> {
         type KK = oa::ReferencedOrInlineSchema;
         use oa::ReferencedOrInlineSchema::*;
@@ -105,7 +186,8 @@ impl oa::Schema {
             match k {
                 Inline(x) => {
                     // Type-A sum-type. Name-based variants.
-                    if !(x.r#type.str() != "object") { return err(&path, "sum-type variant must be a JSON Object type in OpenAPI schema") }
+                    if x.r#type.str() != "object" { return err(&path, "sum-type variant must be a JSON Object type in OpenAPI schema") }
+                    let deprecated = x.scan_deprecation();
                     let reqs = x.required.guard(&path, "name-based sum-type variant node's properties must be all required")?;
                     let props = x.properties.guard(&path, "name-based sum-type variant node must have 1 property")?;
                     for req in reqs {
@@ -119,8 +201,10 @@ impl oa::Schema {
                             Referenced(x) => {
                                 z.push(KSumTypeVariant {
                                     name: x.scan_referenced_type_name(subpath.clone()).to_string(),
-                                    content: KContentStorage { optional: false, array: false, r#type: KTypeRef::Def(x.scan_referenced_type_name(subpath.clone()).to_string()) },
-                                    comment: String::new(),
+                                    tag: name.to_string(),
+                                    content: KContentStorage { optional: false, nullable: false, array: false, r#type: KTypeRef::Def(x.scan_referenced_type_name(subpath.clone()).to_string()) },
+                                    comment: x.scan_composed_comment(subpath.clone()),
+                                    deprecated: deprecated.clone(),
                                 });
                             },
                         }
@@ -132,19 +216,31 @@ impl oa::Schema {
                     // Type-B sum-type. Type-based variants.
                     z.push(KSumTypeVariant {
                         name: x.scan_referenced_type_name(subpath.clone()).to_string(),
-                        content: KContentStorage { optional: false, array: false, r#type: KTypeRef::Def(x.scan_referenced_type_name(subpath.clone()).to_string()) },
-                        comment: String::new(),
+                        tag: self.scan_sum_type_variant_tag(x.scan_referenced_type_name(subpath.clone())),
+                        content: KContentStorage { optional: false, nullable: false, array: false, r#type: KTypeRef::Def(x.scan_referenced_type_name(subpath.clone()).to_string()) },
+                        comment: x.scan_composed_comment(subpath.clone()),
+                        deprecated: None,
                     });
                 },
             }
         }
         Ok(z)
     }
+    /// Finds serialized discriminant value for a type-based variant.
+    /// Falls back to the type name if `discriminator.mapping` has no entry for it.
+    fn scan_sum_type_variant_tag(&self, type_name: &str) -> String {
+        let mapping = self.discriminator.as_ref().and_then(|x| x.mapping.as_ref());
+        for (tag,target) in mapping.into_iter().flatten() {
+            if target.rsplit('/').next() == Some(type_name) { return tag.clone() }
+        }
+        type_name.to_string()
+    }
     fn scan_prod_type(&self, path: lint::Path, name: &str) -> Result<KProdType> {
         let z = KProdType {
             name: name.to_string(),
             fields: self.scan_prod_type_fields(path.clone())?,
             comment: self.scan_composed_comment(path.clone()),
+            attrs: self.scan_type_attrs(path.clone()),
         };
         Ok(z)
     }
@@ -161,35 +257,85 @@ impl oa::Schema {
                     name: name.to_string(),
                     content: KContentStorage {
                         optional: optional,
+                        nullable: false,
                         array: false,
-                        r#type: KTypeRef::Def(x.scan_referenced_type_name(subpath).to_string()),
+                        r#type: KTypeRef::Def(x.scan_referenced_type_name(subpath.clone()).to_string()),
                     },
-                    comment: "".to_string(),
+                    constraints: KConstraints::default(),
+                    default: None,
+                    comment: x.scan_composed_comment(subpath),
+                    deprecated: None,
+                }),
+                // A reference annotated by wrapping `allOf`.
+                Inline(x) if x.wrapped_reference().is_some() => z.push(KProdTypeField {
+                    name: name.to_string(),
+                    content: KContentStorage {
+                        optional: optional,
+                        nullable: x.nullable,
+                        array: false,
+                        r#type: KTypeRef::Def(x.wrapped_reference().map(|x| x.scan_referenced_type_name(subpath.clone())).unwrap_or_default().to_string()),
+                    },
+                    constraints: KConstraints::default(),
+                    default: x.default.clone(),
+                    comment: x.scan_composed_comment(subpath),
+                    deprecated: x.scan_deprecation(),
                 }),
                 Inline(x) => z.push(KProdTypeField {
                     name: name.to_string(),
                     content: x.scan_content_type(subpath.clone(), optional)?,
+                    constraints: x.scan_constraints(subpath.clone()),
+                    default: x.default.clone(),
                     comment: x.scan_composed_comment(subpath.clone()),
+                    deprecated: x.scan_deprecation(),
                 }),
             }
         }
         Ok(z)
     }
-    fn scan_composed_comment(&self, _path: lint::Path) -> String {
-        let a = self.title.str();
-        let b = self.summary.str();
-        let c = self.description.str();
-        let mut z = String::new();
-        if a != "" { 
-            z.push_str(a);
-            z.push_str("\n\n");
+    /// Scans `x-kcg-*` extensions for code-gen controls.
+    fn scan_type_attrs(&self, _path: lint::Path) -> KTypeAttrs {
+        KTypeAttrs {
+            skip: self.x_kcg_skip,
+            rust_type: self.x_kcg_rust_type.clone(),
+            derive: self.x_kcg_derive.clone().unwrap_or_default(),
+            deprecated: self.scan_deprecation(),
         }
-        if b != "" { 
-            z.push_str(b);
-            z.push_str("\n\n");
+    }
+    /// Scans deprecation note. `None` if not deprecated.
+    fn scan_deprecation(&self) -> Option<String> {
+        match self.deprecated {
+            true => Some(self.x_kcg_deprecation_note.clone().unwrap_or_default()),
+            false => None,
+        }
+    }
+    /// Scans value constraints.
+    /// Constraints of inline array items apply to each element.
+    fn scan_constraints(&self, _path: lint::Path) -> KConstraints {
+        let element = match self.items.as_deref() {
+            Some(oa::ReferencedOrInlineSchema::Inline(x)) => x,
+            _ => self,
+        };
+        KConstraints {
+            minimum: element.minimum.clone(),
+            maximum: element.maximum.clone(),
+            min_length: element.min_length,
+            max_length: element.max_length,
+            pattern: element.pattern.clone(),
+            min_items: self.min_items,
+            max_items: self.max_items,
+            unique_items: self.unique_items,
         }
-        z.push_str(c);
-        z
+    }
+    /// Composes documentation from annotations.
+    /// Paragraphs are in order of title, summary, description, example and external docs.
+    /// Deprecation is not documented here as code-gen marks it in generated code.
+    fn scan_composed_comment(&self, _path: lint::Path) -> String {
+        let example = self.example.as_ref().map(|x| format!("Example: `{}`", x)).unwrap_or_default();
+        let external_docs = self.external_docs.as_ref().map(|x| match x.description.str() {
+            "" => format!("See <{}>", x.url),
+            description => format!("See [{}]({})", description, x.url),
+        }).unwrap_or_default();
+        compose_comment(&[self.title.str(), self.summary.str(), self.description.str(), &example, &external_docs])
     }
     /// Scans prod-type field's type from a OAS property node.
     fn scan_content_type(&self, path: lint::Path, optional:bool) -> Result<KContentStorage> {
@@ -199,6 +345,7 @@ impl oa::Schema {
                 let x = self.items.guard(&path, "a JSON Array type OAS node must have a `items` property node")?;
                 KContentStorage {
                     optional: optional,
+                    nullable: self.nullable,
                     array: true, 
                     r#type: x.scan_type_ref(path.appending("items"))?,
                 }
@@ -209,6 +356,7 @@ impl oa::Schema {
             // A prim-type.
             _ => KContentStorage {
                 optional: optional,
+                nullable: self.nullable,
                 array: false, 
                 r#type: KTypeRef::Prim(self.scan_prim_type(path)?),
             },
@@ -223,7 +371,14 @@ impl oa::Schema {
             ("integer","int64") => I64,
             ("number","float") => F32,
             ("number","double") => F64,
-            ("string","") => String,
+            ("string","date-time") => DateTime,
+            ("string","date") => Date,
+            ("string","uuid") => Uuid,
+            ("string","uri") => Uri,
+            ("string","byte") => Byte,
+            ("string","binary") => Binary,
+            // Other formats are annotations only.
+            ("string",_) => String,
             (_,_) => return err(&path, "unknown/unsupported type/format combination for KCG primitive type"),
         };
         Ok(x)
@@ -234,6 +389,14 @@ impl oa::Reference {
     fn scan_referenced_type_name(&self, _path: lint::Path) -> &str {
         self.r#ref.split("/").last().unwrap_or("")
     }
+    /// Composes documentation from annotations next to `$ref`.
+    fn scan_composed_comment(&self, _path: lint::Path) -> String {
+        compose_comment(&[self.summary.str(), self.description.str()])
+    }
+}
+/// Joins non-empty paragraphs.
+fn compose_comment(paragraphs: &[&str]) -> String {
+    paragraphs.iter().map(|x| x.trim_end()).filter(|x| !x.is_empty()).collect::<Vec<_>>().join("\n\n")
 }
 
 
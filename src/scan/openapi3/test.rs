@@ -72,7 +72,9 @@ fn test_reading_new_type_pass() -> Result<(), Box<dyn std::error::Error>> {
     assert_eq!(c.types[0], KType::New(KNewType { 
         name: "Order".to_string(), 
         origin: KTypeRef::Prim(KPrimType::String), 
-        comment: String::new() }));
+        constraints: KConstraints::default(),
+        comment: String::new(),
+        attrs: KTypeAttrs::default() }));
     Ok(())
 }
 
@@ -105,14 +107,19 @@ fn test_reading_enum_type_pass() -> Result<(), Box<dyn std::error::Error>> {
         cases: vec![
             KEnumTypeCase {
                 name: "Whale".to_string(),
+                var_name: None,
                 comment: "".to_string(),
+                deprecated: None,
             },
             KEnumTypeCase {
                 name: "Shrimp".to_string(),
+                var_name: None,
                 comment: "".to_string(),
+                deprecated: None,
             },
         ],
         comment: "".to_string(),
+        attrs: KTypeAttrs::default(),
     }));
     Ok(())
 }
@@ -151,27 +158,202 @@ fn test_reading_sum_type_pass() -> Result<(), Box<dyn std::error::Error>> {
     assert_eq!(c.types[0], KType::Sum(KSumType { 
         name: "Pet".to_string(), 
         discriminant: "type".to_string(),
+        tagging: KSumTypeTagging::Internal,
         variants: vec![
             KSumTypeVariant {
                 name: "Cat".to_string(),
+                tag: "Cat".to_string(),
                 content: KContentStorage {
                     optional: false,
+                    nullable: false,
                     array: false,
                     r#type: KTypeRef::Def("Cat".to_string()),
                 },
                 comment: "".to_string(),
+                deprecated: None,
             },
             KSumTypeVariant {
                 name: "Dog".to_string(),
+                tag: "Dog".to_string(),
                 content: KContentStorage {
                     optional: false,
+                    nullable: false,
                     array: false,
                     r#type: KTypeRef::Def("Dog".to_string()),
                 },
                 comment: "".to_string(),
+                deprecated: None,
             },
         ], 
-        comment: "".to_string() }));
+        comment: "".to_string(),
+        attrs: KTypeAttrs::default() }));
+    Ok(())
+}
+
+#[test]
+fn test_reading_sum_type_mapping_pass() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        components:
+            schemas:
+                Pet: 
+                    type: object
+                    oneOf:
+                        - $ref: '#/components/schemas/Cat'
+                        - $ref: '#/components/schemas/Dog'
+                    discriminator:
+                        propertyName: type
+                        mapping:
+                            cat: '#/components/schemas/Cat'
+                            dog: Dog
+
+                Cat: { type: string }
+                Dog: { type: string }
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a)?;
+    
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    x.check()?;
+
+    let c = b.scan(Path::default())?;
+    match &c.types[0] {
+        KType::Sum(x) => {
+            assert_eq!(x.variants[0].name, "Cat");
+            assert_eq!(x.variants[0].tag, "cat");
+            assert_eq!(x.variants[1].name, "Dog");
+            assert_eq!(x.variants[1].tag, "dog");
+        },
+        _ => panic!("expected sum-type"),
+    }
+    Ok(())
+}
+
+#[test]
+#[should_panic]
+fn test_reading_sum_type_mapping_fail() {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        components:
+            schemas:
+                Pet: 
+                    type: object
+                    oneOf:
+                        - $ref: '#/components/schemas/Cat'
+                    discriminator:
+                        propertyName: type
+                        mapping:
+                            dog: '#/components/schemas/Dog'
+
+                Cat: { type: string }
+                Dog: { type: string }
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a).unwrap();
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    x.check().unwrap();
+}
+
+#[test]
+fn test_reading_sum_type_mapping_shared_target_fail() {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        components:
+            schemas:
+                Pet: 
+                    type: object
+                    oneOf:
+                        - $ref: '#/components/schemas/Cat'
+                    discriminator:
+                        propertyName: type
+                        mapping:
+                            cat: '#/components/schemas/Cat'
+                            kitty: '#/components/schemas/Cat'
+
+                Cat: { type: string }
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a).unwrap();
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    let paths = x.logs.iter().filter(|x| x.severity == Severity::Error).map(|x| x.path.to_string()).collect::<Vec<_>>();
+    assert_eq!(paths, vec!["components/Pet/discriminator/mapping/kitty"]);
+}
+
+#[test]
+fn test_reading_sum_type_tagging_pass() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        components:
+            schemas:
+                Pet1: 
+                    type: object
+                    oneOf:
+                        - type: object
+                          required: [cat]
+                          properties:
+                              cat: { $ref: '#/components/schemas/Cat' }
+                        - type: object
+                          required: [dog]
+                          properties:
+                              dog: { $ref: '#/components/schemas/Dog' }
+                Pet2: 
+                    type: object
+                    oneOf:
+                        - $ref: '#/components/schemas/Cat'
+                        - $ref: '#/components/schemas/Dog'
+                    discriminator:
+                        propertyName: type
+                    x-kcg-tagging: adjacent
+                    x-kcg-content: value
+                Pet3: 
+                    type: object
+                    oneOf:
+                        - $ref: '#/components/schemas/Cat'
+                        - $ref: '#/components/schemas/Dog'
+
+                Cat: { type: string }
+                Dog: { type: string }
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a)?;
+    
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    x.check()?;
+
+    let c = b.scan(Path::default())?;
+    let taggings = c.types.iter().filter_map(|x| match x { KType::Sum(x) => Some(&x.tagging), _ => None }).collect::<Vec<_>>();
+    assert_eq!(taggings, vec![
+        &KSumTypeTagging::External,
+        &KSumTypeTagging::Adjacent { content: "value".to_string() },
+        &KSumTypeTagging::Untagged,
+    ]);
+    match &c.types[0] {
+        KType::Sum(x) => {
+            assert_eq!(x.variants[0].name, "Cat");
+            assert_eq!(x.variants[0].tag, "cat");
+        },
+        _ => panic!("expected sum-type"),
+    }
     Ok(())
 }
 
@@ -218,30 +400,785 @@ fn test_reading_prod_type_pass() -> Result<(), Box<dyn std::error::Error>> {
                 name: "fuel".to_string(),
                 content: KContentStorage {
                     optional: true,
+                    nullable: false,
                     array: false,
                     r#type: KTypeRef::Prim(KPrimType::Bool),
                 },
+                constraints: KConstraints::default(),
+                default: None,
                 comment: "".to_string(),
+                deprecated: None,
             },
             KProdTypeField {
                 name: "cargo".to_string(),
                 content: KContentStorage {
                     optional: false,
+                    nullable: false,
                     array: false,
                     r#type: KTypeRef::Def("Cargo".to_string()),
                 },
+                constraints: KConstraints::default(),
+                default: None,
                 comment: "".to_string(),
+                deprecated: None,
             },
             KProdTypeField {
                 name: "crews".to_string(),
                 content: KContentStorage {
                     optional: false,
+                    nullable: false,
                     array: true,
                     r#type: KTypeRef::Prim(KPrimType::String),
                 },
+                constraints: KConstraints::default(),
+                default: None,
                 comment: "".to_string(),
+                deprecated: None,
+            },
+        ],
+        comment: "".to_string(),
+        attrs: KTypeAttrs::default() }));    
+    Ok(())
+}
+
+#[test]
+fn test_reading_kcg_extensions_pass() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        x-company-note: ignored
+        components:
+            schemas:
+                Ship: 
+                    type: object
+                    properties: 
+                        cargo:
+                            $ref: '#/components/schemas/Cargo'
+                    x-kcg-derive: [Clone, Hash]
+                    x-kcg-rename: Vessel
+                    x-company-note: ignored
+                Cargo: 
+                    type: object
+                    properties: {}
+                    x-kcg-skip: true
+                    x-kcg-rename: Freight
+                Order:
+                    type: string
+                    x-kcg-rust-type: uuid::Uuid
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a)?;
+    
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    x.check()?;
+
+    let c = b.scan(Path::default())?;
+    assert_eq!(c.types[0], KType::Prod(KProdType { 
+        name: "Vessel".to_string(), 
+        fields: vec![
+            KProdTypeField {
+                name: "cargo".to_string(),
+                content: KContentStorage {
+                    optional: true,
+                    nullable: false,
+                    array: false,
+                    r#type: KTypeRef::Def("Freight".to_string()),
+                },
+                constraints: KConstraints::default(),
+                default: None,
+                comment: "".to_string(),
+                deprecated: None,
+            },
+        ],
+        comment: "".to_string(),
+        attrs: KTypeAttrs {
+            skip: false,
+            rust_type: None,
+            derive: vec!["Clone".to_string(), "Hash".to_string()],
+            deprecated: None,
+        } }));
+    assert_eq!(c.types[1].name(), "Freight");
+    assert!(c.types[1].attrs().skip);
+    assert_eq!(c.types[2].attrs().rust_type, Some("uuid::Uuid".to_string()));
+    Ok(())
+}
+
+#[test]
+fn test_reading_unknown_extensions_fail() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        x-kcg-unknown1: 1
+        components:
+            schemas:
+                Order:
+                    type: string
+                    x-kcg-unknown2: 2
+                    unknownProperty: 3
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a)?;
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    let paths = x.logs.iter().map(|x| x.path.to_string()).collect::<Vec<_>>();
+    assert_eq!(paths, vec![
+        "components/Order/x-kcg-unknown2",
+        "components/Order/unknownProperty",
+        "x-kcg-unknown1",
+    ]);
+    Ok(())
+}
+
+#[test]
+fn test_reading_unknown_member_extensions_fail() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        components:
+            schemas:
+                Ship:
+                    type: object
+                    properties:
+                        name:
+                            type: string
+                            minLenght: 1
+                            x-kcg-bogus: 1
+                            x-kcg-rename: Title
+                        speed:
+                            type: number
+                            format: double
+                            multipleOf: 2
+                            x-kcg-skip: true
+                        crews:
+                            type: array
+                            items:
+                                type: string
+                                x-kcg-bogus: 1
+                                x-kcg-rust-type: String
+                        dog:
+                            allOf: [$ref: '#/components/schemas/Dog']
+                            x-kcg-bogus: 1
+                            x-kcg-derive: [Hash]
+                            x-note: vendor extensions are fine
+                Dog:
+                    type: string
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a)?;
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    let paths = x.logs.iter().filter(|x| x.severity == Severity::Error).map(|x| x.path.to_string()).collect::<Vec<_>>();
+    assert_eq!(paths, vec![
+        "components/Ship/name/minLenght",
+        "components/Ship/name/x-kcg-bogus",
+        "components/Ship/name/x-kcg-rename",
+        "components/Ship/speed/multipleOf",
+        "components/Ship/speed/x-kcg-skip",
+        "components/Ship/crews/items/x-kcg-bogus",
+        "components/Ship/crews/items/x-kcg-rust-type",
+        "components/Ship/dog/x-kcg-bogus",
+        "components/Ship/dog/x-kcg-derive",
+    ]);
+    Ok(())
+}
+
+#[test]
+fn test_reading_constraints_pass() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        components:
+            schemas:
+                Ship: 
+                    type: object
+                    properties: 
+                        fuel:
+                            type: number
+                            format: double
+                            minimum: 0
+                            maximum: 1.5
+                        crews:
+                            type: array 
+                            minItems: 1
+                            uniqueItems: true
+                            items: 
+                                type: string
+                                maxLength: 10
+                Code:
+                    type: string
+                    minLength: 4
+                    pattern: '^[0-9]+$'
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a)?;
+    
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    x.check()?;
+
+    let c = b.scan(Path::default())?;
+    let fields = match &c.types[0] {
+        KType::Prod(x) => x.fields.iter().map(|x| x.constraints.clone()).collect::<Vec<_>>(),
+        _ => panic!("expected prod-type"),
+    };
+    assert_eq!(fields, vec![
+        KConstraints {
+            minimum: Some(0.into()),
+            maximum: serde_json::Number::from_f64(1.5),
+            ..KConstraints::default()
+        },
+        KConstraints {
+            max_length: Some(10),
+            min_items: Some(1),
+            unique_items: true,
+            ..KConstraints::default()
+        },
+    ]);
+    match &c.types[1] {
+        KType::New(x) => assert_eq!(x.constraints, KConstraints {
+            min_length: Some(4),
+            pattern: Some("^[0-9]+$".to_string()),
+            ..KConstraints::default()
+        }),
+        _ => panic!("expected new-type"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_reading_misplaced_constraints_fail() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        components:
+            schemas:
+                Ship: 
+                    type: object
+                    properties: 
+                        name:
+                            type: string
+                            minimum: 1
+                        crews:
+                            type: array 
+                            maxLength: 3
+                            items: 
+                                type: string
+                                minItems: 1
+                Code:
+                    type: string
+                    minLength: 4
+                    maxLength: 2
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a)?;
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    let paths = x.logs.iter().map(|x| x.path.to_string()).collect::<Vec<_>>();
+    assert_eq!(paths, vec![
+        "components/Ship/name/minimum",
+        "components/Ship/crews/maxLength",
+        "components/Ship/crews/items/minItems",
+        "components/Code/minLength",
+    ]);
+    Ok(())
+}
+
+#[test]
+fn test_reading_defaults() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        components:
+            schemas:
+                Ship: 
+                    type: object
+                    properties: 
+                        fuel:
+                            type: integer
+                            format: int32
+                            default: 100
+                        crews:
+                            type: array 
+                            items: 
+                                type: string
+                            default: [Jack]
+                        name:
+                            type: string
+                            default: 1
+                        cargos:
+                            type: array 
+                            items: 
+                                $ref: '#/components/schemas/Ship'
+                            default: [{}]
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a)?;
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    let paths = x.logs.iter().map(|x| x.path.to_string()).collect::<Vec<_>>();
+    assert_eq!(paths, vec![
+        "components/Ship/name/default",
+        "components/Ship/cargos/default",
+    ]);
+
+    let c = b.scan(Path::default())?;
+    let defaults = match &c.types[0] {
+        KType::Prod(x) => x.fields.iter().map(|x| x.default.clone()).collect::<Vec<_>>(),
+        _ => panic!("expected prod-type"),
+    };
+    assert_eq!(defaults[..2], [Some(serde_json::json!(100)), Some(serde_json::json!(["Jack"]))]);
+    Ok(())
+}
+
+#[test]
+fn test_reading_string_formats() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        components:
+            schemas:
+                Timestamp:
+                    type: string
+                    format: date-time
+                Ship: 
+                    type: object
+                    properties: 
+                        id:
+                            type: string
+                            format: uuid
+                            maxLength: 36
+                        email:
+                            type: string
+                            format: email
+                        photos:
+                            type: array 
+                            items: 
+                                type: string
+                                format: byte
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a)?;
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    let logs = x.logs.iter().map(|x| (x.severity, x.path.to_string())).collect::<Vec<_>>();
+    assert_eq!(logs, vec![
+        (Severity::Error, "components/Ship/id/maxLength".to_string()),
+        (Severity::Warning, "components/Ship/email/format".to_string()),
+    ]);
+
+    let c = b.scan(Path::default())?;
+    assert_eq!(c.types[0], KType::New(KNewType {
+        name: "Timestamp".to_string(),
+        origin: KTypeRef::Prim(KPrimType::DateTime),
+        constraints: KConstraints::default(),
+        comment: String::new(),
+        attrs: KTypeAttrs::default() }));
+    let types = match &c.types[1] {
+        KType::Prod(x) => x.fields.iter().map(|x| x.content.r#type.clone()).collect::<Vec<_>>(),
+        _ => panic!("expected prod-type"),
+    };
+    assert_eq!(types, vec![
+        KTypeRef::Prim(KPrimType::Uuid),
+        KTypeRef::Prim(KPrimType::String),
+        KTypeRef::Prim(KPrimType::Byte),
+    ]);
+    Ok(())
+}
+
+#[test]
+fn test_reading_nullable() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        components:
+            schemas:
+                Name:
+                    type: string
+                    nullable: true
+                Ship: 
+                    type: object
+                    required: [captain]
+                    properties: 
+                        captain:
+                            type: string
+                            nullable: true
+                        port:
+                            type: string
+                            nullable: true
+                            default: null
+                        crews:
+                            type: array 
+                            items: 
+                                type: string
+                                nullable: true
+                        owner:
+                            allOf: [$ref: '#/components/schemas/Owner']
+                            nullable: true
+                            default: null
+                        cargo:
+                            allOf: [$ref: '#/components/schemas/Owner']
+                            default: Smith
+                        flag:
+                            $ref: '#/components/schemas/Owner'
+                            nullable: true
+                            x-note: ignored
+                Owner:
+                    type: string
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a)?;
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    let logs = x.logs.iter().map(|x| (x.severity, x.path.to_string())).collect::<Vec<_>>();
+    assert_eq!(logs, vec![
+        (Severity::Error, "components/Name/nullable".to_string()),
+        (Severity::Error, "components/Ship/crews/items/nullable".to_string()),
+        (Severity::Error, "components/Ship/cargo/default".to_string()),
+        // Siblings of `$ref` are ignored, so nullable references must be wrapped in `allOf`.
+        (Severity::Error, "components/Ship/flag/nullable".to_string()),
+    ]);
+
+    let c = b.scan(Path::default())?;
+    let contents = match &c.types[1] {
+        KType::Prod(x) => x.fields.iter().map(|x| (x.content.optional, x.content.nullable)).collect::<Vec<_>>(),
+        _ => panic!("expected prod-type"),
+    };
+    assert_eq!(contents, vec![(false, true), (true, true), (true, false), (true, true), (true, false), (true, false)]);
+    match &c.types[1] {
+        KType::Prod(x) => assert_eq!(x.fields[3].default, Some(serde_json::Value::Null)),
+        _ => panic!("expected prod-type"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_reading_unit_variants() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        components:
+            schemas:
+                Event1: 
+                    type: object
+                    oneOf:
+                        - $ref: '#/components/schemas/Ping'
+                        - $ref: '#/components/schemas/Message'
+                    discriminator:
+                        propertyName: type
+                Event2: 
+                    type: object
+                    oneOf:
+                        - $ref: '#/components/schemas/Ping'
+                        - $ref: '#/components/schemas/Message'
+                Event3: 
+                    type: object
+                    oneOf:
+                        - $ref: '#/components/schemas/Pong'
+                        - $ref: '#/components/schemas/Message'
+                    discriminator:
+                        propertyName: type
+                Ping:
+                    type: object
+                    properties: {}
+                Pong:
+                    type: object
+                    properties: {}
+                Message:
+                    type: object
+                    properties:
+                        text: { type: string }
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a)?;
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    x.check()?;
+
+    let c = b.scan(Path::default())?;
+    let types = c.types.iter().filter_map(|x| match x {
+        KType::Sum(x) => Some(x.variants.iter().map(|x| (x.name.as_str(), &x.content.r#type)).collect::<Vec<_>>()),
+        _ => None,
+    }).collect::<Vec<_>>();
+    // Untagged sum-types keep variant content to keep serialized form.
+    assert_eq!(types, vec![
+        vec![("Ping", &KTypeRef::Unit), ("Message", &KTypeRef::Def("Message".to_string()))],
+        vec![("Ping", &KTypeRef::Def("Ping".to_string())), ("Message", &KTypeRef::Def("Message".to_string()))],
+        vec![("Pong", &KTypeRef::Unit), ("Message", &KTypeRef::Def("Message".to_string()))],
+    ]);
+    // Empty prod-types used only as unit variants are not made.
+    assert!(c.types.iter().any(|x| x.name() == "Ping"));
+    assert!(!c.types.iter().any(|x| x.name() == "Pong"));
+    let mut x = Context::default();
+    c.lint(Path::default(), &mut x);
+    assert!(!x.logs.iter().any(|x| x.path.to_string().contains("Pong")));
+    Ok(())
+}
+
+#[test]
+fn test_reading_enum_case_docs() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        components:
+            schemas:
+                Fish: 
+                    type: string
+                    description: Sea animals.
+                    enum: [blue-whale, shrimp]
+                    x-enum-descriptions: [Largest animal., Small one.]
+                    x-enum-varnames: [BLUE_WHALE, Prawn]
+                Bird: 
+                    type: string
+                    enum: [crow, owl]
+                    x-enum-descriptions: [Black.]
+                    x-enum-varnames: [Crow, night-owl]
+                Ship:
+                    type: string
+                    x-enum-descriptions: [Big.]
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a)?;
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    let logs = x.logs.iter().map(|x| (x.severity, x.path.to_string())).collect::<Vec<_>>();
+    assert_eq!(logs, vec![
+        (Severity::Error, "components/Bird/x-enum-descriptions".to_string()),
+        (Severity::Error, "components/Bird/x-enum-varnames/1".to_string()),
+        (Severity::Error, "components/Ship/x-enum-descriptions".to_string()),
+    ]);
+
+    let c = b.scan(Path::default())?;
+    assert_eq!(c.types[0], KType::Enum(KEnumType {
+        name: "Fish".to_string(),
+        cases: vec![
+            KEnumTypeCase {
+                name: "blue-whale".to_string(),
+                var_name: Some("BLUE_WHALE".to_string()),
+                comment: "Largest animal.".to_string(),
+                deprecated: None,
             },
+            KEnumTypeCase {
+                name: "shrimp".to_string(),
+                var_name: Some("Prawn".to_string()),
+                comment: "Small one.".to_string(),
+                deprecated: None,
+            },
+        ],
+        comment: "Sea animals.".to_string(),
+        attrs: KTypeAttrs::default(),
+    }));
+    Ok(())
+}
+
+#[test]
+fn test_reading_reference_docs() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        components:
+            schemas:
+                Pet: 
+                    type: object
+                    oneOf:
+                        - $ref: '#/components/schemas/Cat'
+                          description: A cat.
+                        - $ref: '#/components/schemas/Dog'
+                    discriminator:
+                        propertyName: type
+                Ship: 
+                    type: object
+                    properties: 
+                        cat:
+                            $ref: '#/components/schemas/Cat'
+                            summary: Captain.
+                            description: Cat in charge.
+                        dog:
+                            allOf: [$ref: '#/components/schemas/Dog']
+                            description: Guard.
+                            deprecated: true
+                            example: { name: Max }
+                            externalDocs:
+                                description: Dogs
+                                url: https://example.com/dogs
+                        name:
+                            type: string
+                            externalDocs:
+                                url: https://example.com/names
+                Cat:
+                    type: object
+                    properties:
+                        name: { type: string }
+                Dog:
+                    type: object
+                    properties:
+                        name: { type: string }
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a)?;
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    x.check()?;
+
+    let c = b.scan(Path::default())?;
+    let comments = c.types.iter().take(2).map(|x| match x {
+        KType::Sum(x) => x.variants.iter().map(|x| x.comment.clone()).collect::<Vec<_>>(),
+        KType::Prod(x) => x.fields.iter().map(|x| x.comment.clone()).collect::<Vec<_>>(),
+        _ => panic!("expected sum/prod-type"),
+    }).collect::<Vec<_>>();
+    assert_eq!(comments, vec![
+        vec!["A cat.".to_string(), "".to_string()],
+        vec![
+            "Captain.\n\nCat in charge.".to_string(),
+            "Guard.\n\nExample: `{\"name\":\"Max\"}`\n\nSee [Dogs](https://example.com/dogs)".to_string(),
+            "See <https://example.com/names>".to_string(),
         ],
-        comment: "".to_string() }));    
+    ]);
+    match &c.types[1] {
+        KType::Prod(x) => assert_eq!(x.fields[1].content.r#type, KTypeRef::Def("Dog".to_string())),
+        _ => panic!("expected prod-type"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_reading_reference_wrapper_fail() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        components:
+            schemas:
+                Ship: 
+                    type: object
+                    properties: 
+                        dog:
+                            type: object
+                            allOf: [$ref: '#/components/schemas/Dog']
+                        cat:
+                            type: string
+                            allOf: [{ type: string }]
+                        fox:
+                            allOf: [$ref: '#/components/schemas/Dog']
+                            maxLength: 3
+                            format: uuid
+                            minItems: 1
+                            pattern: '^a'
+                            additionalProperties: true
+                Dog:
+                    type: string
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a)?;
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    let logs = x.logs.iter().map(|x| (x.severity, x.path.to_string())).collect::<Vec<_>>();
+    assert_eq!(logs, vec![
+        (Severity::Error, "components/Ship/dog/type".to_string()),
+        (Severity::Error, "components/Ship/cat/allOf".to_string()),
+        // Constraints on wrappers are rejected rather than dropped.
+        (Severity::Error, "components/Ship/fox/format".to_string()),
+        (Severity::Error, "components/Ship/fox/additionalProperties".to_string()),
+        (Severity::Error, "components/Ship/fox/maxLength".to_string()),
+        (Severity::Error, "components/Ship/fox/pattern".to_string()),
+        (Severity::Error, "components/Ship/fox/minItems".to_string()),
+    ]);
+    Ok(())
+}
+
+#[test]
+fn test_reading_deprecations() -> Result<(), Box<dyn std::error::Error>> {
+    let a = indoc!(r#"
+        openapi: 3.0.1
+        info:
+            title: Swagger Petstore
+            version: 1.2.3
+        paths: {}
+        components:
+            schemas:
+                Flag: 
+                    type: string
+                    enum: [red, blue]
+                    x-kcg-deprecated-cases: [red, green]
+                Cargo:
+                    type: object
+                    properties: {}
+                    deprecated: true
+                    x-kcg-deprecation-note: Use `Ship.load`.
+                Ship: 
+                    type: object
+                    properties: 
+                        cargo:
+                            allOf: [$ref: '#/components/schemas/Cargo']
+                            deprecated: true
+                        name:
+                            type: string
+                            x-kcg-deprecation-note: Not deprecated.
+    "#);
+    let b = serde_yaml::from_str::<Doc>(a)?;
+    let mut x = Context::default();
+    b.lint(Path::default(), &mut x);
+    println!("{}", x);
+    let logs = x.logs.iter().map(|x| (x.severity, x.path.to_string())).collect::<Vec<_>>();
+    assert_eq!(logs, vec![
+        (Severity::Error, "components/Flag/x-kcg-deprecated-cases/1".to_string()),
+        (Severity::Error, "components/Ship/name/x-kcg-deprecation-note".to_string()),
+    ]);
+
+    let c = b.scan(Path::default())?;
+    match &c.types[0] {
+        KType::Enum(x) => assert_eq!(x.cases.iter().map(|x| x.deprecated.clone()).collect::<Vec<_>>(), vec![Some("".to_string()), None]),
+        _ => panic!("expected enum-type"),
+    }
+    assert_eq!(c.types[1].attrs().deprecated, Some("Use `Ship.load`.".to_string()));
+    match &c.types[2] {
+        KType::Prod(x) => assert_eq!(x.fields.iter().map(|x| x.deprecated.clone()).collect::<Vec<_>>(), vec![Some("".to_string()), None]),
+        _ => panic!("expected prod-type"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_ir_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let a = serde_yaml::from_str::<Doc>(include_str!("../../../tests/sample1/src.yaml"))?;
+    let b = a.scan(Path::default())?;
+    let c = serde_json::to_string_pretty(&b)?;
+    let d = serde_json::from_str::<crate::model::Doc1>(&c)?;
+    assert_eq!(b, d);
     Ok(())
 }
@@ -0,0 +1,45 @@
+#![cfg(test)]
+
+use super::*;
+
+#[test]
+fn test_gen_options_merge() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let config = toml::from_str::<config::Config>(indoc::indoc!(r#"
+        input = "api1.yaml"
+
+        [targets.rust]
+        builder = true
+        validate = true
+        format-types = { date-time = "chrono", uuid = "uuid" }
+    "#))?;
+    let mut a = GenOpt::from_iter_safe(["gen", "--format-type", "uuid=String"])?;
+    a.merge(&config);
+    assert_eq!(a.model.input, Some("api1.yaml".to_string()));
+    assert!(a.builders);
+    assert!(a.validation);
+    assert_eq!(a.format_types, vec![
+        ("date-time".to_string(), "chrono".to_string()),
+        ("uuid".to_string(), "String".to_string()),
+    ]);
+    // Command line options override configured values.
+    let mut b = GenOpt::from_iter_safe(["gen", "other.yaml", "--no-builder", "--no-validate"])?;
+    b.merge(&config);
+    assert_eq!(b.model.input, Some("other.yaml".to_string()));
+    assert!(!b.builders);
+    assert!(!b.validation);
+    assert!(GenOpt::from_iter_safe(["gen", "--builder", "--no-builder"]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_gen_through_builder() {
+    let log = Logger { level: lint::Severity::Error, config: config::Config::default() };
+    let model = |input: &str| ModelOpt { input: Some(input.to_string()), ir: false, skippings: vec![], collision_prefix: None, collision_suffix: None };
+    let code = model_builder(&model("tests/sample1/src.yaml"), &log).unwrap().generate_reporting(&mut |_| ()).unwrap();
+    assert!(code.contains("pub struct Ship {"));
+    // Lint errors reject input, and other errors are I/O failures.
+    let e = model_builder(&model("tests/sample1/src.yaml"), &log).unwrap().derive("serde::").generate_reporting(&mut |_| ()).unwrap_err();
+    assert!(matches!(builder_failure(e), Failure::Rejected(_)));
+    let e = model_builder(&model("tests/no-such-file.yaml"), &log).unwrap().generate_reporting(&mut |_| ()).unwrap_err();
+    assert!(matches!(builder_failure(e), Failure::Io(_)));
+}
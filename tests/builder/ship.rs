@@ -0,0 +1,53 @@
+use serde_derive::{Serialize, Deserialize};
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub struct Ship {
+    pub fuel: Option<bool>,
+    pub name: String,
+    pub crews: Vec<String>,
+}
+#[derive(Default)]
+pub struct ShipBuilder {
+    fuel: Option<bool>,
+    name: Option<String>,
+    crews: Option<Vec<String>>,
+}
+impl ShipBuilder {
+    pub fn fuel(mut self, value: bool) -> Self {
+        self.fuel = Some(value);
+        self
+    }
+    pub fn name(mut self, value: String) -> Self {
+        self.name = Some(value);
+        self
+    }
+    pub fn crews(mut self, value: Vec<String>) -> Self {
+        self.crews = Some(value);
+        self
+    }
+    /// Builds `Ship`.
+    /// Fails with names of missing required fields.
+    pub fn build(self) -> Result<Ship, Vec<&'static str>> {
+        let mut missing = Vec::new();
+        if self.name.is_none() {
+            missing.push("name");
+        }
+        if self.crews.is_none() {
+            missing.push("crews");
+        }
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+        Ok(Ship {
+            fuel: self.fuel,
+            name: self.name.unwrap(),
+            crews: self.crews.unwrap(),
+        })
+    }
+}
+impl Ship {
+    pub fn builder() -> ShipBuilder {
+        ShipBuilder::default()
+    }
+}
@@ -0,0 +1,40 @@
+use serde_derive::{Serialize, Deserialize};
+#[derive(Serialize, Deserialize)]
+#[derive(PartialEq)]
+#[derive(Debug)]
+pub struct Ship {
+    #[serde(default = "Ship::default_name")]
+    pub name: String,
+    #[serde(default = "Ship::default_speed")]
+    pub speed: f64,
+    #[serde(default = "Ship::default_crews")]
+    pub crews: Vec<String>,
+    #[serde(default = "Ship::default_fuel")]
+    pub fuel: Option<i32>,
+    pub r#type: Option<bool>,
+}
+impl Ship {
+    fn default_name() -> String {
+        "Unnamed \"ship\"".to_string()
+    }
+    fn default_speed() -> f64 {
+        1.0
+    }
+    fn default_crews() -> Vec<String> {
+        vec!["Jack".to_string(), "Rose".to_string()]
+    }
+    fn default_fuel() -> Option<i32> {
+        Some(100)
+    }
+}
+impl Default for Ship {
+    fn default() -> Self {
+        Ship {
+            name: Ship::default_name(),
+            speed: Ship::default_speed(),
+            crews: Ship::default_crews(),
+            fuel: Ship::default_fuel(),
+            r#type: None,
+        }
+    }
+}
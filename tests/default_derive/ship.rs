@@ -0,0 +1,92 @@
+use serde_derive::{Serialize, Deserialize};
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub enum Fish {
+    Whale,
+}
+impl std::str::FromStr for Fish {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Fish::*;
+        match s {
+            "Whale" => Ok(Whale),
+            _ => Err("unknown case name".to_string()),
+        }
+    }
+}
+impl std::string::ToString for Fish {
+    fn to_string(&self) -> String {
+        use Fish::*;
+        match self {
+            Whale => "Whale".to_string(),
+        }
+    }
+}
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub enum Pet {
+    Cat(Cat),
+}
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+#[serde(try_from = "String")]
+pub struct Code(String);
+impl std::convert::TryFrom<String> for Code {
+    type Error = &'static str;
+    fn try_from(inner: String) -> Result<Self, Self::Error> {
+        let value = &inner;
+        if value.chars().count() < 1 {
+            return Err("must be at least 1 characters long");
+        }
+        Ok(Code(inner))
+    }
+}
+impl From<Code> for String {
+    fn from(x: Code) -> String {
+        x.0
+    }
+}
+impl std::ops::Deref for Code {
+    type Target = String;
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+#[derive(Default)]
+pub struct Cat {
+    pub name: String,
+}
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub struct Ship {
+    pub fish: Fish,
+    pub pet: Option<Pet>,
+}
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub struct Fleet {
+    pub flagship: Ship,
+}
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub struct Crew {
+    pub code: Code,
+}
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+#[derive(Default)]
+pub struct Tank {
+    pub fishes: Vec<Fish>,
+    pub pet: Option<Pet>,
+    pub cat: Cat,
+}
@@ -0,0 +1,182 @@
+use serde_derive::{Serialize, Deserialize};
+/// A constraint violation found by `Validate`.
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub struct ValidationError {
+    /// JSON pointer to the violating value.
+    pub path: String,
+    pub message: String,
+}
+/// Constraint violations found by `Validate`.
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub struct ValidationErrors(pub Vec<ValidationError>);
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for x in self.0.iter() {
+            writeln!(f, "{}: {}", x.path, x.message)?;
+        }
+        Ok(())
+    }
+}
+impl std::error::Error for ValidationErrors {}
+/// Checks schema constraints which cannot be checked by type system.
+pub trait Validate {
+    /// Collects violations into `errors`.
+    /// - `path`: JSON pointer to `self`.
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>);
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = Vec::new();
+        self.validate_at("", &mut errors);
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(ValidationErrors(errors)),
+        }
+    }
+}
+#[allow(deprecated)]
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub enum Flag {
+    #[deprecated]
+    Red,
+    Blue,
+}
+#[allow(deprecated)]
+impl std::str::FromStr for Flag {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Flag::*;
+        match s {
+            "Red" => Ok(Red),
+            "Blue" => Ok(Blue),
+            _ => Err("unknown case name".to_string()),
+        }
+    }
+}
+#[allow(deprecated)]
+impl std::string::ToString for Flag {
+    fn to_string(&self) -> String {
+        use Flag::*;
+        match self {
+            Red => "Red".to_string(),
+            Blue => "Blue".to_string(),
+        }
+    }
+}
+#[allow(deprecated)]
+#[deprecated(note = "Use `Ship::load`.")]
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub struct Cargo {
+    pub weight: i32,
+}
+#[allow(deprecated)]
+#[derive(Default)]
+pub struct CargoBuilder {
+    weight: Option<i32>,
+}
+#[allow(deprecated)]
+impl CargoBuilder {
+    pub fn weight(mut self, value: i32) -> Self {
+        self.weight = Some(value);
+        self
+    }
+    /// Builds `Cargo`.
+    /// Fails with names of missing required fields.
+    pub fn build(self) -> Result<Cargo, Vec<&'static str>> {
+        let mut missing = Vec::new();
+        if self.weight.is_none() {
+            missing.push("weight");
+        }
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+        Ok(Cargo {
+            weight: self.weight.unwrap(),
+        })
+    }
+}
+#[allow(deprecated)]
+impl Cargo {
+    pub fn builder() -> CargoBuilder {
+        CargoBuilder::default()
+    }
+}
+#[allow(deprecated)]
+impl Validate for Cargo {
+    fn validate_at(&self, _path: &str, _errors: &mut Vec<ValidationError>) {}
+}
+#[allow(deprecated)]
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub struct Ship {
+    pub name: String,
+    pub flag: Flag,
+    #[deprecated]
+    pub cargo: Cargo,
+}
+#[allow(deprecated)]
+#[derive(Default)]
+pub struct ShipBuilder {
+    name: Option<String>,
+    flag: Option<Flag>,
+    cargo: Option<Cargo>,
+}
+#[allow(deprecated)]
+impl ShipBuilder {
+    pub fn name(mut self, value: String) -> Self {
+        self.name = Some(value);
+        self
+    }
+    pub fn flag(mut self, value: Flag) -> Self {
+        self.flag = Some(value);
+        self
+    }
+    #[deprecated]
+    pub fn cargo(mut self, value: Cargo) -> Self {
+        self.cargo = Some(value);
+        self
+    }
+    /// Builds `Ship`.
+    /// Fails with names of missing required fields.
+    pub fn build(self) -> Result<Ship, Vec<&'static str>> {
+        let mut missing = Vec::new();
+        if self.name.is_none() {
+            missing.push("name");
+        }
+        if self.flag.is_none() {
+            missing.push("flag");
+        }
+        if self.cargo.is_none() {
+            missing.push("cargo");
+        }
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+        Ok(Ship {
+            name: self.name.unwrap(),
+            flag: self.flag.unwrap(),
+            cargo: self.cargo.unwrap(),
+        })
+    }
+}
+#[allow(deprecated)]
+impl Ship {
+    pub fn builder() -> ShipBuilder {
+        ShipBuilder::default()
+    }
+}
+#[allow(deprecated)]
+impl Validate for Ship {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        {
+            let path = format!("{}/cargo", path);
+            let value = &self.cargo;
+            value.validate_at(&path, errors);
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use serde_derive::{Serialize, Deserialize};
+/// Bytes serialized as a base64 string. (`format: byte`)
+#[derive(Clone)]
+#[derive(Default)]
+#[derive(Eq, PartialEq)]
+#[derive(Hash)]
+#[derive(Debug)]
+pub struct Base64Bytes(pub Vec<u8>);
+impl serde::Serialize for Base64Bytes {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use base64::Engine;
+        serializer
+            .serialize_str(&base64::engine::general_purpose::STANDARD.encode(&self.0))
+    }
+}
+impl<'de> serde::Deserialize<'de> for Base64Bytes {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        use base64::Engine;
+        let x = <String as serde::Deserialize>::deserialize(deserializer)?;
+        base64::engine::general_purpose::STANDARD
+            .decode(x)
+            .map(Base64Bytes)
+            .map_err(serde::de::Error::custom)
+    }
+}
+impl std::ops::Deref for Base64Bytes {
+    type Target = Vec<u8>;
+    fn deref(&self) -> &Vec<u8> {
+        &self.0
+    }
+}
+pub type Timestamp = chrono::DateTime<chrono::Utc>;
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub struct Ship {
+    pub id: uuid::Uuid,
+    pub launched: chrono::NaiveDate,
+    pub updated: Timestamp,
+    pub home: Option<String>,
+    pub photos: Vec<Base64Bytes>,
+}
@@ -0,0 +1,31 @@
+/// Flight code.
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[serde(try_from = "String")]
+pub struct Code(String);
+impl std::convert::TryFrom<String> for Code {
+    type Error = &'static str;
+    fn try_from(inner: String) -> Result<Self, Self::Error> {
+        let value = &inner;
+        if value.chars().count() < 2 {
+            return Err("must be at least 2 characters long");
+        }
+        if !regex::Regex::new("^[0-9]+$").is_ok_and(|x| x.is_match(value)) {
+            return Err("must match pattern `^[0-9]+$`");
+        }
+        Ok(Code(inner))
+    }
+}
+impl From<Code> for String {
+    fn from(x: Code) -> String {
+        x.0
+    }
+}
+impl std::ops::Deref for Code {
+    type Target = String;
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
@@ -0,0 +1,27 @@
+use serde_derive::{Serialize, Deserialize};
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub struct Ship {
+    pub name: String,
+    pub captain: Option<String>,
+    pub flag: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    pub port: Option<Option<String>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    pub cabin: Option<Option<Cabin>>,
+}
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub struct Cabin {
+    pub number: i32,
+}
@@ -3,7 +3,7 @@ set -e
 
 rm -rf ./tmp
 mkdir -p ./tmp/sample1/src
-cargo run -- tests/sample1/src.yaml tmp/sample1/src/lib.rs --include ./tests/sample1/prelude.rs --skip Ship
+cargo run -- gen tests/sample1/src.yaml tmp/sample1/src/lib.rs --include ./tests/sample1/prelude.rs --skip Ship
 
 cd tmp/sample1
 cargo init --lib
@@ -0,0 +1,7 @@
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub enum Pet {
+    Cat(Cat),
+    Dog(Dog),
+}
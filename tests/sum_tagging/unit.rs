@@ -0,0 +1,8 @@
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+#[serde(tag = "type")]
+pub enum Event {
+    Ping,
+    Cat(Cat),
+}
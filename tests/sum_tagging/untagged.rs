@@ -0,0 +1,8 @@
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+#[serde(untagged)]
+pub enum Pet {
+    Cat(Cat),
+    Dog(Dog),
+}
@@ -0,0 +1,160 @@
+use serde_derive::{Serialize, Deserialize};
+/// A constraint violation found by `Validate`.
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub struct ValidationError {
+    /// JSON pointer to the violating value.
+    pub path: String,
+    pub message: String,
+}
+/// Constraint violations found by `Validate`.
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub struct ValidationErrors(pub Vec<ValidationError>);
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for x in self.0.iter() {
+            writeln!(f, "{}: {}", x.path, x.message)?;
+        }
+        Ok(())
+    }
+}
+impl std::error::Error for ValidationErrors {}
+/// Checks schema constraints which cannot be checked by type system.
+pub trait Validate {
+    /// Collects violations into `errors`.
+    /// - `path`: JSON pointer to `self`.
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>);
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = Vec::new();
+        self.validate_at("", &mut errors);
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(ValidationErrors(errors)),
+        }
+    }
+}
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub struct Ship {
+    pub name: String,
+    pub crews: Vec<String>,
+    pub fuel: Option<i32>,
+    pub code: Code,
+}
+impl Validate for Ship {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        {
+            let path = format!("{}/name", path);
+            let value = &self.name;
+            if value.chars().count() < 1 {
+                errors
+                    .push(ValidationError {
+                        path: path.to_string(),
+                        message: "must be at least 1 characters long".to_string(),
+                    });
+            }
+            if !regex::Regex::new("^[A-Z]").is_ok_and(|x| x.is_match(value)) {
+                errors
+                    .push(ValidationError {
+                        path: path.to_string(),
+                        message: "must match pattern `^[A-Z]`".to_string(),
+                    });
+            }
+        }
+        {
+            let path = format!("{}/crews", path);
+            let value = &self.crews;
+            if value.len() > 3 {
+                errors
+                    .push(ValidationError {
+                        path: path.to_string(),
+                        message: "must have at most 3 items".to_string(),
+                    });
+            }
+            if (1..value.len()).any(|i| value[..i].contains(&value[i])) {
+                errors
+                    .push(ValidationError {
+                        path: path.to_string(),
+                        message: "must have unique items".to_string(),
+                    });
+            }
+            for (i, value) in value.iter().enumerate() {
+                let path = format!("{}/{}", path, i);
+                if value.chars().count() > 8 {
+                    errors
+                        .push(ValidationError {
+                            path: path.to_string(),
+                            message: "must be at most 8 characters long".to_string(),
+                        });
+                }
+            }
+        }
+        {
+            let path = format!("{}/fuel", path);
+            if let Some(value) = &self.fuel {
+                if *value < 0 {
+                    errors
+                        .push(ValidationError {
+                            path: path.to_string(),
+                            message: "must be greater than or equal to 0".to_string(),
+                        });
+                }
+                if *value > 100 {
+                    errors
+                        .push(ValidationError {
+                            path: path.to_string(),
+                            message: "must be less than or equal to 100".to_string(),
+                        });
+                }
+            }
+        }
+    }
+}
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+#[serde(try_from = "String")]
+pub struct Code(String);
+impl std::convert::TryFrom<String> for Code {
+    type Error = &'static str;
+    fn try_from(inner: String) -> Result<Self, Self::Error> {
+        let value = &inner;
+        if !regex::Regex::new("^[0-9]+$").is_ok_and(|x| x.is_match(value)) {
+            return Err("must match pattern `^[0-9]+$`");
+        }
+        Ok(Code(inner))
+    }
+}
+impl From<Code> for String {
+    fn from(x: Code) -> String {
+        x.0
+    }
+}
+impl std::ops::Deref for Code {
+    type Target = String;
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
+#[derive(Serialize, Deserialize)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug)]
+pub enum Fleet {
+    #[serde(rename = "ship")]
+    Ship(Vec<Ship>),
+}
+impl Validate for Fleet {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        match self {
+            Fleet::Ship(value) => {
+                let path = format!("{}/ship", path);
+                for (i, value) in value.iter().enumerate() {
+                    let path = format!("{}/{}", path, i);
+                    value.validate_at(&path, errors);
+                }
+            }
+        }
+    }
+}